@@ -0,0 +1,209 @@
+// Advent of Code 2024 - Day 3
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::lexer::MAX_ARGUMENT_DIGITS;
+
+/// Interpreter state that instructions operate on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub enabled: bool,
+    pub result: i64,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            enabled: true,
+            result: 0,
+        }
+    }
+}
+
+/// An instruction of the form `name(a,b,...)`, where every argument is 1-3 digits.
+pub trait Operation {
+    fn name(&self) -> &str;
+    fn arity(&self) -> usize;
+    fn execute(&self, state: &mut State, arguments: &[i64]);
+}
+
+pub struct Mul;
+
+impl Operation for Mul {
+    fn name(&self) -> &str {
+        "mul"
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn execute(&self, state: &mut State, arguments: &[i64]) {
+        if state.enabled {
+            state.result += arguments[0] * arguments[1];
+        }
+    }
+}
+
+pub struct Do;
+
+impl Operation for Do {
+    fn name(&self) -> &str {
+        "do"
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn execute(&self, state: &mut State, _arguments: &[i64]) {
+        state.enabled = true;
+    }
+}
+
+pub struct Dont;
+
+impl Operation for Dont {
+    fn name(&self) -> &str {
+        "don't"
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn execute(&self, state: &mut State, _arguments: &[i64]) {
+        state.enabled = false;
+    }
+}
+
+pub struct Add;
+
+impl Operation for Add {
+    fn name(&self) -> &str {
+        "add"
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn execute(&self, state: &mut State, arguments: &[i64]) {
+        if state.enabled {
+            state.result += arguments[0] + arguments[1];
+        }
+    }
+}
+
+pub struct Neg;
+
+impl Operation for Neg {
+    fn name(&self) -> &str {
+        "neg"
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, state: &mut State, arguments: &[i64]) {
+        if state.enabled {
+            state.result -= arguments[0];
+        }
+    }
+}
+
+pub struct Reset;
+
+impl Operation for Reset {
+    fn name(&self) -> &str {
+        "reset"
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn execute(&self, state: &mut State, _arguments: &[i64]) {
+        state.result = 0;
+    }
+}
+
+/// Looks up one of the instructions shipped with this program by name.
+pub fn builtin(name: &str) -> Option<Box<dyn Operation>> {
+    match name {
+        "mul" => Some(Box::new(Mul)),
+        "do" => Some(Box::new(Do)),
+        "don't" => Some(Box::new(Dont)),
+        "add" => Some(Box::new(Add)),
+        "neg" => Some(Box::new(Neg)),
+        "reset" => Some(Box::new(Reset)),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    operations: Vec<Box<dyn Operation>>,
+}
+
+impl Registry {
+    /// The instruction set of part 2: `mul`, `do` and `don't`.
+    pub fn with_builtins() -> Self {
+        Registry::default()
+            .with(Box::new(Mul))
+            .with(Box::new(Do))
+            .with(Box::new(Dont))
+    }
+
+    pub fn with(mut self, operation: Box<dyn Operation>) -> Self {
+        self.register(operation);
+        self
+    }
+
+    pub fn register(&mut self, operation: Box<dyn Operation>) {
+        assert!(
+            self.find(operation.name()).is_none(),
+            "instruction {} is already registered",
+            operation.name()
+        );
+        self.operations.push(operation);
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.operations.iter().position(|op| op.name() == name)
+    }
+
+    pub fn get(&self, opcode: usize) -> &dyn Operation {
+        self.operations[opcode].as_ref()
+    }
+
+    /// Length in bytes of the longest instruction that can be matched, e.g. 12 for `mul(123,456)`.
+    pub fn max_instruction_length(&self) -> usize {
+        self.operations
+            .iter()
+            .map(|op| {
+                let separators = op.arity().saturating_sub(1);
+                op.name().len() + "()".len() + op.arity() * MAX_ARGUMENT_DIGITS + separators
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &dyn Operation)> {
+        self.operations
+            .iter()
+            .enumerate()
+            .map(|(opcode, op)| (opcode, op.as_ref()))
+    }
+}
@@ -0,0 +1,79 @@
+// Advent of Code 2024 - Day 3
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{
+    instructions::{Registry, State},
+    lexer::{Instruction, Token},
+};
+
+pub struct Interpreter<'a> {
+    registry: &'a Registry,
+    state: State,
+    trace: bool,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(registry: &'a Registry) -> Self {
+        Interpreter {
+            registry,
+            state: State::default(),
+            trace: false,
+        }
+    }
+
+    /// Print every recognized instruction together with its byte offset.
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    pub fn execute(&mut self, token: &Token) {
+        let operation = self.registry.get(token.instruction.opcode);
+        operation.execute(&mut self.state, &token.instruction.arguments);
+
+        if self.trace {
+            let state = if self.state.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            };
+            println!(
+                "{:>8}: {} ({state})",
+                token.offset,
+                self.format(&token.instruction)
+            );
+        }
+    }
+
+    pub fn run(mut self, tokens: impl IntoIterator<Item = Token>) -> i64 {
+        for token in tokens {
+            self.execute(&token);
+        }
+        self.state.result
+    }
+
+    fn format(&self, instruction: &Instruction) -> String {
+        let arguments: Vec<String> = instruction
+            .arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+
+        format!(
+            "{}({})",
+            self.registry.get(instruction.opcode).name(),
+            arguments.join(",")
+        )
+    }
+}
@@ -0,0 +1,182 @@
+// Advent of Code 2024 - Day 3
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::instructions::Registry;
+use std::io::{self, ErrorKind, Read};
+
+/// Arguments are limited to 1-3 digits.
+pub const MAX_ARGUMENT_DIGITS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// Index of the operation in the [`Registry`].
+    pub opcode: usize,
+    pub arguments: Vec<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Byte offset of the first character of the instruction in the input.
+    pub offset: u64,
+    /// Length of the instruction in bytes.
+    pub length: usize,
+    pub instruction: Instruction,
+}
+
+/// Number of bytes requested from the reader at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Scans corrupted memory for instructions known to the registry, everything else is skipped.
+///
+/// The input is read in chunks and treated as raw bytes, so it does not have to be valid UTF-8 or
+/// fit in memory. Enough bytes to hold the longest possible instruction are always kept buffered,
+/// so instructions split across chunk boundaries are still recognized.
+pub struct Lexer<'a, R> {
+    registry: &'a Registry,
+    reader: R,
+    buffer: Vec<u8>,
+    /// Position of the next byte to scan in `buffer`.
+    position: usize,
+    /// Offset in the input of the first byte in `buffer`.
+    buffer_offset: u64,
+    lookahead: usize,
+    eof: bool,
+}
+
+impl<'a, R: Read> Lexer<'a, R> {
+    pub fn new(registry: &'a Registry, reader: R) -> Self {
+        Lexer {
+            registry,
+            reader,
+            buffer: Vec::new(),
+            position: 0,
+            buffer_offset: 0,
+            lookahead: registry.max_instruction_length(),
+            eof: false,
+        }
+    }
+
+    /// Drops the already scanned bytes and appends the next chunk from the reader.
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.position);
+        self.buffer_offset += self.position as u64;
+        self.position = 0;
+
+        let length = self.buffer.len();
+        self.buffer.resize(length + CHUNK_SIZE, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buffer[length..]) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buffer.truncate(length);
+                    return Err(e);
+                }
+            }
+        };
+        self.buffer.truncate(length + read);
+
+        if read == 0 {
+            self.eof = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Lexer<'_, R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<io::Result<Token>> {
+        loop {
+            if !self.eof && self.buffer.len() - self.position < self.lookahead {
+                if let Err(e) = self.fill_buffer() {
+                    return Some(Err(e));
+                }
+                continue;
+            }
+
+            if self.position >= self.buffer.len() {
+                return None;
+            }
+
+            let offset = self.buffer_offset + self.position as u64;
+            if let Some((instruction, length)) =
+                match_instruction(self.registry, &self.buffer[self.position..])
+            {
+                self.position += length;
+                return Some(Ok(Token {
+                    offset,
+                    length,
+                    instruction,
+                }));
+            }
+            self.position += 1;
+        }
+    }
+}
+
+/// Tries to match an instruction at the start of `input`, returns the instruction and its length.
+fn match_instruction(registry: &Registry, input: &[u8]) -> Option<(Instruction, usize)> {
+    registry.iter().find_map(|(opcode, operation)| {
+        let arguments_start = operation.name().len() + 1;
+        let rest = input
+            .strip_prefix(operation.name().as_bytes())?
+            .strip_prefix(b"(")?;
+
+        let (arguments, arguments_length) = match_arguments(rest, operation.arity())?;
+        rest[arguments_length..].strip_prefix(b")")?;
+
+        let instruction = Instruction { opcode, arguments };
+        Some((instruction, arguments_start + arguments_length + 1))
+    })
+}
+
+/// Matches `arity` comma separated arguments, returns the values and the number of bytes consumed.
+fn match_arguments(input: &[u8], arity: usize) -> Option<(Vec<i64>, usize)> {
+    let mut arguments = Vec::with_capacity(arity);
+    let mut length = 0;
+
+    for index in 0..arity {
+        if index > 0 {
+            input[length..].strip_prefix(b",")?;
+            length += 1;
+        }
+
+        let (value, digits) = match_argument(&input[length..])?;
+        arguments.push(value);
+        length += digits;
+    }
+
+    Some((arguments, length))
+}
+
+/// Matches 1-3 ASCII digits, returns the value and the number of digits consumed.
+fn match_argument(input: &[u8]) -> Option<(i64, usize)> {
+    let length = input
+        .iter()
+        .take(MAX_ARGUMENT_DIGITS + 1)
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+
+    if length == 0 || length > MAX_ARGUMENT_DIGITS {
+        return None;
+    }
+
+    let value = input[..length]
+        .iter()
+        .fold(0, |value, c| value * 10 + i64::from(c - b'0'));
+
+    Some((value, length))
+}
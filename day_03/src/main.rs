@@ -12,45 +12,67 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use regex::Regex;
-use std::fs;
+mod instructions;
+mod interpreter;
+mod lexer;
 
+use instructions::{Mul, Registry};
+use interpreter::Interpreter;
+use lexer::Lexer;
+use std::{env, fs::File};
+
+/// Usage: `day_03 [--trace] [--with add,neg,reset] [FILE]`
+///
+/// `--trace` lists every recognized instruction and its byte offset, `--with` adds extra
+/// instructions to the part 2 instruction set.
 fn main() {
-    let input = fs::read_to_string("input.txt").unwrap();
+    let mut trace = false;
+    let mut extra_instructions = Vec::new();
+    let mut filename = None;
 
-    // Part 1
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => trace = true,
+            "--with" => {
+                let names = args.next().expect("--with requires a list of instructions");
+                for name in names.split(',') {
+                    extra_instructions.push(
+                        instructions::builtin(name)
+                            .unwrap_or_else(|| panic!("unknown instruction {name}")),
+                    );
+                }
+            }
+            _ => filename = Some(arg),
+        }
+    }
 
-    let result: i32 = re
-        .captures_iter(&input)
-        .map(|c| c.extract())
-        .map(|(_, [lhs, rhs])| lhs.parse::<i32>().unwrap() * rhs.parse::<i32>().unwrap())
-        .sum();
+    let is_puzzle = filename.is_none() && extra_instructions.is_empty();
+    let filename = filename.as_deref().unwrap_or("input.txt");
+
+    // Part 1
+    let registry = Registry::default().with(Box::new(Mul));
+    let result = Interpreter::new(&registry)
+        .with_trace(trace)
+        .run(Lexer::new(&registry, File::open(filename).unwrap()).map(Result::unwrap));
 
     println!("{result}");
+    if is_puzzle {
+        assert_eq!(result, 170778545);
+    }
 
     // Part 2
-    let re =
-        Regex::new(r"(mul\((?<lhs>\d+),(?<rhs>\d+)\)|(?<do>do)\(\)|(?<dont>don\'t)\(\))").unwrap();
-
-    let mut enabled = true;
-    let mut result = 0;
-    for cap in re.captures_iter(&input) {
-        if let (Some(lhs), Some(rhs)) = (cap.name("lhs"), cap.name("rhs")) {
-            let lhs = lhs.as_str().parse::<i32>().unwrap();
-            let rhs = rhs.as_str().parse::<i32>().unwrap();
-
-            if enabled {
-                result += lhs * rhs;
-            }
-        } else if let Some(_) = cap.name("do") {
-            enabled = true;
-        } else if let Some(_) = cap.name("dont") {
-            enabled = false;
-        } else {
-            unreachable!();
-        }
+    let mut registry = Registry::with_builtins();
+    for instruction in extra_instructions {
+        registry.register(instruction);
     }
 
+    let result = Interpreter::new(&registry)
+        .with_trace(trace)
+        .run(Lexer::new(&registry, File::open(filename).unwrap()).map(Result::unwrap));
+
     println!("{result}");
+    if is_puzzle {
+        assert_eq!(result, 82868252);
+    }
 }
@@ -0,0 +1,114 @@
+// Advent of Code 2024 - Day 4
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::collections::{HashMap, VecDeque};
+
+const ROOT: usize = 0;
+
+#[derive(Default)]
+struct Node {
+    transitions: HashMap<char, usize>,
+    fail: usize,
+    /// Indices of the patterns ending in this node, including those reachable through `fail`.
+    outputs: Vec<usize>,
+}
+
+/// Multi-pattern matcher, finds every occurrence of every pattern in a single pass over the input.
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+    pattern_lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let mut nodes = vec![Node::default()];
+        let mut pattern_lengths = Vec::new();
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut state = ROOT;
+            for c in pattern.as_ref().chars() {
+                state = match nodes[state].transitions.get(&c) {
+                    Some(next) => *next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[state].transitions.insert(c, next);
+                        next
+                    }
+                };
+            }
+            nodes[state].outputs.push(index);
+            pattern_lengths.push(pattern.as_ref().chars().count());
+        }
+
+        // Breadth first, so the fail node of a parent is always complete before its children
+        let mut queue: VecDeque<usize> = nodes[ROOT].transitions.values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let transitions: Vec<(char, usize)> = nodes[state]
+                .transitions
+                .iter()
+                .map(|(c, next)| (*c, *next))
+                .collect();
+
+            for (c, next) in transitions {
+                let mut fail = nodes[state].fail;
+                while fail != ROOT && !nodes[fail].transitions.contains_key(&c) {
+                    fail = nodes[fail].fail;
+                }
+                let fail = match nodes[fail].transitions.get(&c) {
+                    Some(fail) if *fail != next => *fail,
+                    _ => ROOT,
+                };
+
+                nodes[next].fail = fail;
+                let inherited = nodes[fail].outputs.clone();
+                nodes[next].outputs.extend(inherited);
+                queue.push_back(next);
+            }
+        }
+
+        AhoCorasick {
+            nodes,
+            pattern_lengths,
+        }
+    }
+
+    pub fn pattern_length(&self, pattern: usize) -> usize {
+        self.pattern_lengths[pattern]
+    }
+
+    /// Calls `on_match(pattern, end)` for every occurrence, where `end` is the index of the last
+    /// character of the occurrence in `input`.
+    pub fn find_all(
+        &self,
+        input: impl Iterator<Item = char>,
+        mut on_match: impl FnMut(usize, usize),
+    ) {
+        let mut state = ROOT;
+        for (index, c) in input.enumerate() {
+            while state != ROOT && !self.nodes[state].transitions.contains_key(&c) {
+                state = self.nodes[state].fail;
+            }
+            state = self.nodes[state]
+                .transitions
+                .get(&c)
+                .copied()
+                .unwrap_or(ROOT);
+
+            for pattern in &self.nodes[state].outputs {
+                on_match(*pattern, index);
+            }
+        }
+    }
+}
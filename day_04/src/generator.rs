@@ -0,0 +1,319 @@
+// Advent of Code 2024 - Day 4
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::word_search::{self, Direction, WordMatch};
+use std::{collections::HashSet, fs};
+
+/// Random positions tried for each word before giving up on the current grid.
+const PLACEMENT_ATTEMPTS: usize = 1000;
+/// Grids generated before giving up, a grid is rejected if the filler creates extra words.
+const GENERATION_ATTEMPTS: usize = 100;
+/// Stands in for unfilled cells while searching the placed words, never part of a word.
+const EMPTY: char = '\0';
+
+/// Small xorshift generator, good enough for puzzles and reproducible from a seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub word: String,
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+}
+
+pub struct Puzzle {
+    pub grid: Vec<Vec<char>>,
+    /// Every occurrence of every word in the grid.
+    pub key: Vec<Placement>,
+}
+
+pub struct Generator {
+    width: usize,
+    height: usize,
+    alphabet: Option<Vec<char>>,
+    rng: Rng,
+}
+
+impl Generator {
+    pub fn new(width: usize, height: usize) -> Self {
+        Generator {
+            width,
+            height,
+            alphabet: None,
+            rng: Rng(0x2024_0004),
+        }
+    }
+
+    /// Letters used to fill the cells not covered by a word, defaults to the letters of the words.
+    pub fn with_alphabet(mut self, alphabet: &str) -> Self {
+        self.alphabet = Some(alphabet.chars().collect());
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        // xorshift gets stuck on zero
+        self.rng = Rng(seed.max(1));
+        self
+    }
+
+    /// Places every word in the grid, overlapping words where their letters agree, and fills the
+    /// remaining cells so that no other occurrences of the words appear.
+    ///
+    /// Occurrences formed by the placed letters alone, e.g. a word that is part of another word,
+    /// are intended and included in the key.
+    pub fn generate(&mut self, words: &[&str]) -> Puzzle {
+        let alphabet = self.alphabet.clone().unwrap_or_else(|| {
+            let mut letters: Vec<char> = words.iter().flat_map(|word| word.chars()).collect();
+            letters.sort();
+            letters.dedup();
+            letters
+        });
+        assert!(!alphabet.is_empty(), "empty alphabet");
+
+        // Longest words first, they are the hardest to fit
+        let mut words = words.to_vec();
+        words.sort_by_key(|word| std::cmp::Reverse(word.chars().count()));
+
+        let mut unique_words = words.clone();
+        unique_words.sort();
+        unique_words.dedup();
+
+        for _ in 0..GENERATION_ATTEMPTS {
+            let Some(grid) = self.place_words(&words) else {
+                continue;
+            };
+
+            let placed: Vec<Vec<char>> = grid
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.unwrap_or(EMPTY)).collect())
+                .collect();
+            let key: Vec<Placement> = word_search::find_words(&placed, &unique_words)
+                .into_iter()
+                .map(|m| to_placement(&unique_words, m))
+                .collect();
+
+            let Some(grid) = self.fill(grid, &unique_words, &key, &alphabet) else {
+                continue;
+            };
+
+            if verify(&grid, &key) {
+                return Puzzle { grid, key };
+            }
+        }
+
+        panic!(
+            "unable to generate a {}x{} puzzle for {words:?}",
+            self.width, self.height
+        );
+    }
+
+    fn place_words(&mut self, words: &[&str]) -> Option<Vec<Vec<Option<char>>>> {
+        let mut grid = vec![vec![None; self.width]; self.height];
+        let mut placements = Vec::new();
+
+        for word in words {
+            let letters: Vec<char> = word.chars().collect();
+
+            let placement = (0..PLACEMENT_ATTEMPTS).find_map(|_| {
+                let placement = Placement {
+                    word: word.to_string(),
+                    x: self.rng.below(self.width),
+                    y: self.rng.below(self.height),
+                    direction: Direction::ALL[self.rng.below(Direction::ALL.len())],
+                };
+
+                let cells = self.cells(&placement, letters.len())?;
+                let fits = cells
+                    .iter()
+                    .zip(&letters)
+                    .all(|((x, y), c)| grid[*y][*x].is_none_or(|cell| cell == *c));
+                (fits && !placements.contains(&placement)).then_some((placement, cells))
+            });
+
+            let (placement, cells) = placement?;
+            for ((x, y), c) in cells.into_iter().zip(&letters) {
+                grid[y][x] = Some(*c);
+            }
+            placements.push(placement);
+        }
+
+        Some(grid)
+    }
+
+    /// Fills the empty cells one by one, choosing letters that do not complete an occurrence of a
+    /// word that is not in the key.
+    fn fill(
+        &mut self,
+        mut grid: Vec<Vec<Option<char>>>,
+        words: &[&str],
+        key: &[Placement],
+        alphabet: &[char],
+    ) -> Option<Vec<Vec<char>>> {
+        let key: HashSet<&Placement> = key.iter().collect();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if grid[y][x].is_some() {
+                    continue;
+                }
+
+                let offset = self.rng.below(alphabet.len());
+                let c = (0..alphabet.len())
+                    .map(|i| alphabet[(offset + i) % alphabet.len()])
+                    .find(|c| {
+                        grid[y][x] = Some(*c);
+                        self.new_occurrences(&grid, words, (x, y))
+                            .all(|placement| key.contains(&placement))
+                    });
+                grid[y][x] = Some(c?);
+            }
+        }
+
+        Some(
+            grid.into_iter()
+                .map(|row| row.into_iter().map(Option::unwrap).collect())
+                .collect(),
+        )
+    }
+
+    /// Every complete occurrence of a word passing through the given cell.
+    fn new_occurrences<'a>(
+        &'a self,
+        grid: &'a [Vec<Option<char>>],
+        words: &'a [&str],
+        (x, y): (usize, usize),
+    ) -> impl Iterator<Item = Placement> + 'a {
+        words.iter().flat_map(move |word| {
+            let letters: Vec<char> = word.chars().collect();
+            Direction::ALL.into_iter().flat_map(move |direction| {
+                let (dx, dy) = direction.delta();
+                let letters = letters.clone();
+                (0..letters.len() as i32).filter_map(move |i| {
+                    let start_x = x as i32 - i * dx;
+                    let start_y = y as i32 - i * dy;
+                    if start_x < 0 || start_y < 0 {
+                        return None;
+                    }
+
+                    let placement = Placement {
+                        word: word.to_string(),
+                        x: start_x as usize,
+                        y: start_y as usize,
+                        direction,
+                    };
+                    let cells = self.cells(&placement, letters.len())?;
+                    cells
+                        .iter()
+                        .zip(&letters)
+                        .all(|((x, y), c)| grid[*y][*x] == Some(*c))
+                        .then_some(placement)
+                })
+            })
+        })
+    }
+
+    /// The cells covered by a word of the given length, `None` if it does not fit in the grid.
+    fn cells(&self, placement: &Placement, length: usize) -> Option<Vec<(usize, usize)>> {
+        let (dx, dy) = placement.direction.delta();
+        (0..length as i32)
+            .map(|i| {
+                let x = placement.x as i32 + i * dx;
+                let y = placement.y as i32 + i * dy;
+                let inside = x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32;
+                inside.then_some((x as usize, y as usize))
+            })
+            .collect()
+    }
+}
+
+fn to_placement(words: &[&str], word_match: WordMatch) -> Placement {
+    Placement {
+        word: words[word_match.word].to_string(),
+        x: word_match.x,
+        y: word_match.y,
+        direction: word_match.direction,
+    }
+}
+
+impl Puzzle {
+    /// Writes the grid to `puzzle_filename` and one `WORD x y Direction` line per occurrence to
+    /// `key_filename`.
+    pub fn write(&self, puzzle_filename: &str, key_filename: &str) {
+        let grid: String = self
+            .grid
+            .iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect();
+        fs::write(puzzle_filename, grid).unwrap();
+
+        let key: String = self
+            .key
+            .iter()
+            .map(|p| format!("{} {} {} {:?}\n", p.word, p.x, p.y, p.direction))
+            .collect();
+        fs::write(key_filename, key).unwrap();
+    }
+}
+
+pub fn read_key(filename: &str) -> Vec<Placement> {
+    let contents = fs::read_to_string(filename).unwrap();
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let [word, x, y, direction] = line.split_whitespace().collect::<Vec<&str>>()[..] else {
+                panic!("invalid key line: {line}")
+            };
+
+            Placement {
+                word: word.to_string(),
+                x: x.parse().unwrap(),
+                y: y.parse().unwrap(),
+                direction: Direction::ALL
+                    .into_iter()
+                    .find(|d| format!("{d:?}") == direction)
+                    .unwrap_or_else(|| panic!("invalid direction: {direction}")),
+            }
+        })
+        .collect()
+}
+
+/// Runs the word search on a puzzle and checks that it finds exactly the occurrences in the key.
+pub fn verify(grid: &[Vec<char>], key: &[Placement]) -> bool {
+    let mut words: Vec<&str> = key.iter().map(|p| p.word.as_str()).collect();
+    words.sort();
+    words.dedup();
+
+    let found: HashSet<Placement> = word_search::find_words(grid, &words)
+        .into_iter()
+        .map(|m| to_placement(&words, m))
+        .collect();
+
+    found.len() == key.len() && key.iter().all(|p| found.contains(p))
+}
@@ -12,7 +12,14 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::fs;
+mod aho_corasick;
+mod generator;
+mod shape;
+mod word_search;
+
+use generator::Generator;
+use shape::Template;
+use std::{env, fs};
 
 fn read_puzzle_input(filename: &str) -> Vec<Vec<char>> {
     let contents = fs::read_to_string(filename).unwrap();
@@ -29,92 +36,74 @@ fn read_puzzle_input(filename: &str) -> Vec<Vec<char>> {
     matrix
 }
 
-fn rotate_ccw(matrix: &[Vec<char>]) -> Vec<Vec<char>> {
-    let mut result = Vec::new();
-
-    let max_x = matrix.first().unwrap().len() - 1;
-
-    for _ in matrix.first().unwrap() {
-        result.push(Vec::new());
-    }
-
-    for row in matrix.iter() {
-        for (x, c) in row.iter().enumerate() {
-            result.get_mut(max_x - x).unwrap().push(*c);
+/// Places the words in a new grid and writes the puzzle and its answer key.
+fn generate(args: &[String]) {
+    let [width, height, words, puzzle_filename, key_filename, options @ ..] = args else {
+        panic!("usage: generate <width> <height> <word,...> <puzzle> <key> [--alphabet <letters>] [--seed <n>]")
+    };
+
+    let mut generator = Generator::new(width.parse().unwrap(), height.parse().unwrap());
+    for option in options.chunks(2) {
+        match option {
+            [name, value] if name == "--alphabet" => generator = generator.with_alphabet(value),
+            [name, value] if name == "--seed" => {
+                generator = generator.with_seed(value.parse().unwrap())
+            }
+            _ => panic!("invalid option: {option:?}"),
         }
     }
 
-    result
+    let words: Vec<&str> = words.split(',').collect();
+    let puzzle = generator.generate(&words);
+    puzzle.write(puzzle_filename, key_filename);
+
+    println!("{}", puzzle.key.len());
 }
 
-fn has_word_at_coordinates(
-    matrix: &[Vec<char>],
-    coordinates: &[(usize, usize)],
-    word: &str,
-) -> bool {
-    assert_eq!(coordinates.len(), word.len());
-
-    for ((x, y), expected_c) in coordinates.iter().zip(word.chars()) {
-        let c = matrix.get(*y).and_then(|line| line.get(*x));
-        if let Some(c) = c {
-            if *c != expected_c {
-                return false;
-            }
-        } else {
-            return false;
-        }
-    }
+/// Counts the words of the answer key in the puzzle and checks the occurrences match the key.
+fn verify(args: &[String]) {
+    let [puzzle_filename, key_filename] = args else {
+        panic!("usage: verify <puzzle> <key>")
+    };
+
+    let matrix = read_puzzle_input(puzzle_filename);
+    let key = generator::read_key(key_filename);
 
-    true
+    let mut words: Vec<&str> = key.iter().map(|p| p.word.as_str()).collect();
+    words.sort();
+    words.dedup();
+    let count = word_search::find_words(&matrix, &words).len();
+
+    println!("{count}");
+    assert_eq!(count, key.len());
+    assert!(generator::verify(&matrix, &key));
 }
 
+/// Usage:
+///
+/// - `day_04` solves `input.txt`
+/// - `day_04 generate <width> <height> <word,...> <puzzle> <key> [--alphabet <letters>] [--seed <n>]`
+/// - `day_04 verify <puzzle> <key>`
 fn main() {
-    let matrix = read_puzzle_input("input.txt");
-
-    let mut result_1 = 0;
-    let mut result_2 = 0;
-
-    let mut matrix = matrix;
-    for _ in 0..4 {
-        for (y, row) in matrix.iter().enumerate() {
-            for (x, _) in row.iter().enumerate() {
-                if has_word_at_coordinates(
-                    &matrix,
-                    &[(x, y), (x + 1, y), (x + 2, y), (x + 3, y)],
-                    "XMAS",
-                ) {
-                    result_1 += 1;
-                }
-
-                if has_word_at_coordinates(
-                    &matrix,
-                    &[(x, y), (x + 1, y + 1), (x + 2, y + 2), (x + 3, y + 3)],
-                    "XMAS",
-                ) {
-                    result_1 += 1;
-                }
-
-                if has_word_at_coordinates(
-                    &matrix,
-                    &[(x + 2, y), (x + 1, y + 1), (x, y + 2)],
-                    "MAS",
-                ) && has_word_at_coordinates(
-                    &matrix,
-                    &[(x, y), (x + 1, y + 1), (x + 2, y + 2)],
-                    "MAS",
-                ) {
-                    result_2 += 1;
-                }
-            }
-        }
-        matrix = rotate_ccw(&matrix);
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("generate") => return generate(&args[1..]),
+        Some("verify") => return verify(&args[1..]),
+        _ => (),
     }
 
+    let matrix = read_puzzle_input("input.txt");
+
     // Part 1
+    let result_1 = word_search::find_words(&matrix, &["XMAS"]).len();
+
     println!("{result_1}");
     assert_eq!(result_1, 2496);
 
     // Part 2
+    let x_mas = Template::parse("M.S / .A. / M.S");
+    let result_2 = shape::find_shapes(&matrix, &x_mas).len();
+
     println!("{result_2}");
     assert_eq!(result_2, 1967);
 }
@@ -0,0 +1,137 @@
+// Advent of Code 2024 - Day 4
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+/// Matches any character.
+const WILDCARD: char = '.';
+
+/// A rectangular 2D pattern, `None` cells match any character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    cells: Vec<Vec<Option<char>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeMatch {
+    /// Top left corner of the bounding box.
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Template {
+    /// Parses a template where rows are separated by `/` or newlines, e.g. `M.S / .A. / M.S`.
+    pub fn parse(template: &str) -> Template {
+        let cells: Vec<Vec<Option<char>>> = template
+            .split(['/', '\n'])
+            .map(|row| row.trim())
+            .filter(|row| !row.is_empty())
+            .map(|row| {
+                row.chars()
+                    .map(|c| if c == WILDCARD { None } else { Some(c) })
+                    .collect()
+            })
+            .collect();
+
+        assert!(!cells.is_empty(), "empty template");
+        let width = cells[0].len();
+        assert!(
+            cells.iter().all(|row| row.len() == width),
+            "template rows must have equal width: {template}"
+        );
+
+        Template { cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn rotate_cw(&self) -> Template {
+        let cells = (0..self.width())
+            .map(|x| (0..self.height()).rev().map(|y| self.cells[y][x]).collect())
+            .collect();
+
+        Template { cells }
+    }
+
+    fn reflect(&self) -> Template {
+        let cells = self
+            .cells
+            .iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+
+        Template { cells }
+    }
+
+    /// All distinct templates reachable by rotating and reflecting this one.
+    pub fn variants(&self) -> Vec<Template> {
+        let mut variants: Vec<Template> = Vec::new();
+
+        for mut template in [self.clone(), self.reflect()] {
+            for _ in 0..4 {
+                if !variants.contains(&template) {
+                    variants.push(template.clone());
+                }
+                template = template.rotate_cw();
+            }
+        }
+
+        variants
+    }
+
+    fn matches_at(&self, matrix: &[Vec<char>], x: usize, y: usize) -> bool {
+        self.cells.iter().enumerate().all(|(dy, row)| {
+            row.iter().enumerate().all(|(dx, expected)| {
+                let c = matrix.get(y + dy).and_then(|line| line.get(x + dx));
+                match (expected, c) {
+                    (_, None) => false,
+                    (None, Some(_)) => true,
+                    (Some(expected), Some(c)) => expected == c,
+                }
+            })
+        })
+    }
+}
+
+/// Finds every occurrence of the template under any combination of rotations and reflections.
+///
+/// Symmetric templates are only matched once per occurrence, e.g. the reflection of `M.S / .A. /
+/// M.S` is one of its rotations.
+pub fn find_shapes(matrix: &[Vec<char>], template: &Template) -> Vec<ShapeMatch> {
+    let mut result = Vec::new();
+
+    for variant in template.variants() {
+        for (y, row) in matrix.iter().enumerate() {
+            for x in 0..row.len() {
+                if variant.matches_at(matrix, x, y) {
+                    result.push(ShapeMatch {
+                        x,
+                        y,
+                        width: variant.width(),
+                        height: variant.height(),
+                    });
+                }
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,143 @@
+// Advent of Code 2024 - Day 4
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::aho_corasick::AhoCorasick;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+    North,
+    NorthEast,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::North,
+        Direction::NorthEast,
+    ];
+
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::East => Direction::West,
+            Direction::SouthEast => Direction::NorthWest,
+            Direction::South => Direction::North,
+            Direction::SouthWest => Direction::NorthEast,
+            Direction::West => Direction::East,
+            Direction::NorthWest => Direction::SouthEast,
+            Direction::North => Direction::South,
+            Direction::NorthEast => Direction::SouthWest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordMatch {
+    /// Index of the word in the list passed to [`find_words`].
+    pub word: usize,
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+}
+
+/// Returns the direction and starting cell of every row, column, diagonal and anti-diagonal of the
+/// matrix.
+fn scan_lines(width: i32, height: i32) -> Vec<(Direction, (i32, i32))> {
+    let mut lines = Vec::new();
+
+    for y in 0..height {
+        lines.push((Direction::East, (0, y)));
+        lines.push((Direction::SouthEast, (0, y)));
+        lines.push((Direction::SouthWest, (width - 1, y)));
+    }
+    for x in 0..width {
+        lines.push((Direction::South, (x, 0)));
+        if x > 0 {
+            lines.push((Direction::SouthEast, (x, 0)));
+        }
+        if x < width - 1 {
+            lines.push((Direction::SouthWest, (x, 0)));
+        }
+    }
+
+    lines
+}
+
+/// Finds every occurrence of every word in all 8 directions.
+///
+/// The matrix is searched line by line with a single automaton containing all words and their
+/// reverses, so only 4 of the 8 directions have to be walked.
+pub fn find_words(matrix: &[Vec<char>], words: &[&str]) -> Vec<WordMatch> {
+    let height = matrix.len() as i32;
+    let width = matrix.first().map_or(0, |row| row.len()) as i32;
+
+    // Pattern 2 * i is word i, pattern 2 * i + 1 is word i reversed
+    let patterns: Vec<String> = words
+        .iter()
+        .flat_map(|word| [word.to_string(), word.chars().rev().collect()])
+        .collect();
+    let automaton = AhoCorasick::new(&patterns);
+
+    let mut result = Vec::new();
+    for (direction, (start_x, start_y)) in scan_lines(width, height) {
+        let (dx, dy) = direction.delta();
+        let cells = (0..)
+            .map(|i| (start_x + i * dx, start_y + i * dy))
+            .take_while(|(x, y)| *x >= 0 && *y >= 0 && *x < width && *y < height);
+        let chars = cells.map(|(x, y)| matrix[y as usize][x as usize]);
+
+        automaton.find_all(chars, |pattern, end| {
+            let end = end as i32;
+            let length = automaton.pattern_length(pattern) as i32;
+
+            let (index, direction) = if pattern % 2 == 0 {
+                (end - (length - 1), direction)
+            } else {
+                (end, direction.opposite())
+            };
+
+            result.push(WordMatch {
+                word: pattern / 2,
+                x: (start_x + index * dx) as usize,
+                y: (start_y + index * dy) as usize,
+                direction,
+            });
+        });
+    }
+
+    result
+}
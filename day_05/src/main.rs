@@ -12,108 +12,116 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::{
-    cmp::Ordering,
-    collections::{HashMap, HashSet},
-    fs,
-};
+mod print_queue;
+mod rule_graph;
+mod validator;
 
-fn read_puzzle_input(filename: &str) -> (HashMap<i32, Vec<i32>>, Vec<Vec<i32>>) {
-    let contents = fs::read_to_string(filename).unwrap();
-
-    let mut ordering: HashMap<i32, Vec<i32>> = HashMap::new();
-    let mut iter = contents.split('\n');
-    let mut order = iter.next().unwrap();
-    while !order.is_empty() {
-        let [lhs, rhs] = order.split('|').collect::<Vec<&str>>()[..] else {
-            panic!()
-        };
-        let lhs: i32 = lhs.parse().unwrap();
-        let rhs: i32 = rhs.parse().unwrap();
-        if let Some(order) = ordering.get_mut(&lhs) {
-            order.push(rhs);
-        } else {
-            ordering.insert(lhs, vec![rhs]);
-        }
-        order = iter.next().unwrap();
-    }
+use print_queue::PrintQueue;
+use rule_graph::RuleGraph;
+use std::{collections::HashMap, env, fs};
 
-    let mut seq = iter.next().unwrap();
-    let mut sequences = Vec::new();
-    while !seq.is_empty() {
-        sequences.push(seq.split(',').map(|v| v.parse::<i32>().unwrap()).collect());
-        seq = iter.next().unwrap();
-    }
+fn read_puzzle_input(filename: &str) -> PrintQueue {
+    let contents = fs::read_to_string(filename).unwrap();
 
-    (ordering, sequences)
+    PrintQueue::parse(&contents).unwrap_or_else(|e| panic!("{filename}: {e}"))
 }
 
-fn is_valid_sequence(sequence: &[i32], ordering: &HashMap<i32, Vec<i32>>) -> bool {
-    for (index, value) in sequence.iter().enumerate() {
-        if let Some(after_values) = ordering.get(value) {
-            for after_value in after_values {
-                if let Some(after_index) = sequence.iter().position(|v| *v == *after_value) {
-                    if index > after_index {
-                        return false;
-                    }
-                }
+/// Prints why an update was rejected and which pages have to move to fix it.
+fn print_report(sequence: &[i32], ordering: &HashMap<i32, Vec<i32>>) {
+    let pages: Vec<String> = sequence.iter().map(|page| page.to_string()).collect();
+    println!("{}", pages.join(","));
+
+    for violation in validator::validate(ordering, sequence) {
+        println!("  {violation}");
+    }
+    match validator::minimal_moves(ordering, sequence) {
+        Ok(moves) => {
+            for page_move in moves {
+                println!("  {page_move}");
             }
         }
+        Err(e) => println!("  unable to correct: {e}"),
     }
-    true
 }
 
-fn is_less(lhs: i32, rhs: i32, ordering: &HashMap<i32, Vec<i32>>, sequence: &[i32]) -> bool {
-    let mut visited_numbers = HashSet::new();
-    let mut node_stack = vec![ordering.get(&lhs).unwrap()];
+/// Returns the sum of the middle pages of the valid updates and of the corrected invalid ones.
+/// Updates whose order can not be corrected are printed with the reason and left out.
+fn sum_middle_pages(print_queue: &PrintQueue, report: bool) -> (i32, i32) {
+    let ordering = &print_queue.ordering;
+    let mut result_1 = 0;
+    let mut result_2 = 0;
 
-    while let Some(current_node) = node_stack.pop() {
-        for child_node in current_node {
-            assert_ne!(*child_node, lhs);
-            if *child_node == rhs {
-                return true;
-            }
+    for sequence in &print_queue.updates {
+        if validator::validate(ordering, sequence).is_empty() {
+            result_1 += sequence.get(sequence.len() / 2).unwrap();
+            continue;
+        }
+
+        if report {
+            print_report(sequence, ordering);
+        }
 
-            if sequence.contains(child_node) && !visited_numbers.contains(child_node) {
-                // TODO can this unwrap?
-                node_stack.push(ordering.get(child_node).unwrap());
-                visited_numbers.insert(child_node);
+        match RuleGraph::for_update(ordering, sequence).topological_sort() {
+            Ok(sequence_sorted) => {
+                result_2 += sequence_sorted.get(sequence_sorted.len() / 2).unwrap();
+            }
+            Err(e) => {
+                let pages: Vec<String> = sequence.iter().map(|page| page.to_string()).collect();
+                println!("{}: {e}", pages.join(","));
             }
         }
     }
 
-    false
+    (result_1, result_2)
 }
 
+/// Usage: `day_05 [--report] [FILE]`
+///
+/// `--report` lists the broken rules and the fewest page moves for every invalid update.
 fn main() {
-    let (ordering, sequences) = read_puzzle_input("input.txt");
-
-    let mut result_1 = 0;
-    let mut result_2 = 0;
-
-    for sequence in sequences {
-        if is_valid_sequence(&sequence, &ordering) {
-            result_1 += sequence.get(sequence.len() / 2).unwrap();
-        } else {
-            let mut sequence_sorted = sequence.clone();
-            sequence_sorted.sort_by(|lhs, rhs| {
-                if is_less(*lhs, *rhs, &ordering, &sequence) {
-                    Ordering::Less
-                } else if is_less(*rhs, *lhs, &ordering, &sequence) {
-                    Ordering::Greater
-                } else {
-                    panic!()
-                }
-            });
-            result_2 += sequence_sorted.get(sequence_sorted.len() / 2).unwrap();
+    let mut report = false;
+    let mut filename = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--report" => report = true,
+            _ => filename = Some(arg),
         }
     }
 
+    let is_puzzle = filename.is_none();
+    let print_queue = read_puzzle_input(filename.as_deref().unwrap_or("input.txt"));
+    let (result_1, result_2) = sum_middle_pages(&print_queue, report);
+
     // Part 1
     println!("{result_1}");
-    assert_eq!(result_1, 6051);
+    if is_puzzle {
+        assert_eq!(result_1, 6051);
+    }
 
     // Part 2
     println!("{result_2}");
-    assert_eq!(result_2, 5093);
+    if is_puzzle {
+        assert_eq!(result_2, 5093);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_that_can_not_be_corrected_are_left_out() {
+        let print_queue = PrintQueue::parse("1|2\n2|1\n3|4\n\n1,2\n4,3,5\n3,4,5\n").unwrap();
+        // 1|2 and 2|1 form a cycle, 3 and 5 are not ordered by any rule
+        assert_eq!(sum_middle_pages(&print_queue, false), (4, 0));
+
+        let print_queue = PrintQueue::parse("3|4\n4|5\n\n5,4,3\n").unwrap();
+        assert_eq!(sum_middle_pages(&print_queue, false), (0, 4));
+    }
+
+    #[test]
+    fn queue_without_updates() {
+        let print_queue = PrintQueue::parse("# rules only\r\n3|4\r\n4|5\r\n").unwrap();
+        assert_eq!(sum_middle_pages(&print_queue, false), (0, 0));
+    }
 }
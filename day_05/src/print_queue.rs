@@ -0,0 +1,86 @@
+// Advent of Code 2024 - Day 5
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{collections::HashMap, fmt};
+
+/// Lines starting with this are ignored.
+const COMMENT: char = '#';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrintQueue {
+    /// For every page, the pages that have to be printed after it.
+    pub ordering: HashMap<i32, Vec<i32>>,
+    pub updates: Vec<Vec<i32>>,
+}
+
+impl PrintQueue {
+    /// Parses `a|b` rules followed by comma separated updates.
+    ///
+    /// LF and CRLF line endings, blank lines and `#` comments are accepted anywhere. The updates
+    /// section may be missing, but a rule may not follow an update.
+    pub fn parse(contents: &str) -> Result<PrintQueue, ParseError> {
+        let mut print_queue = PrintQueue::default();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(COMMENT) {
+                continue;
+            }
+
+            let error = |message: String| ParseError {
+                line: line_number,
+                message,
+            };
+
+            if let Some((lhs, rhs)) = line.split_once('|') {
+                if !print_queue.updates.is_empty() {
+                    return Err(error(format!("rule {line} after the updates")));
+                }
+
+                let lhs = parse_page(lhs).map_err(error)?;
+                let rhs = parse_page(rhs).map_err(error)?;
+                print_queue.ordering.entry(lhs).or_default().push(rhs);
+            } else {
+                let update = line
+                    .split(',')
+                    .map(parse_page)
+                    .collect::<Result<Vec<i32>, String>>()
+                    .map_err(error)?;
+                print_queue.updates.push(update);
+            }
+        }
+
+        Ok(print_queue)
+    }
+}
+
+fn parse_page(page: &str) -> Result<i32, String> {
+    let page = page.trim();
+    page.parse()
+        .map_err(|_| format!("expected a page number, found {page:?}"))
+}
@@ -0,0 +1,133 @@
+// Advent of Code 2024 - Day 5
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    /// The rules between these pages form a cycle, each page must be printed before the next one
+    /// and the last one before the first.
+    Cycle(Vec<i32>),
+    /// No rule decides which of the two pages is printed first.
+    Ambiguous(i32, i32),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::Cycle(pages) => {
+                let pages: Vec<String> = pages.iter().map(|page| page.to_string()).collect();
+                write!(f, "rules form a cycle: {}", pages.join(" -> "))
+            }
+            OrderError::Ambiguous(lhs, rhs) => {
+                write!(f, "no rule orders pages {lhs} and {rhs}")
+            }
+        }
+    }
+}
+
+/// The ordering rules restricted to the pages of a single update.
+pub struct RuleGraph {
+    pages: Vec<i32>,
+    successors: HashMap<i32, Vec<i32>>,
+}
+
+impl RuleGraph {
+    pub fn for_update(ordering: &HashMap<i32, Vec<i32>>, update: &[i32]) -> Self {
+        let successors = update
+            .iter()
+            .map(|page| {
+                let after_pages = ordering.get(page).map_or(Vec::new(), |after_pages| {
+                    after_pages
+                        .iter()
+                        .filter(|after_page| update.contains(after_page))
+                        .copied()
+                        .collect()
+                });
+                (*page, after_pages)
+            })
+            .collect();
+
+        RuleGraph {
+            pages: update.to_vec(),
+            successors,
+        }
+    }
+
+    /// Orders the pages with Kahn's algorithm, the order must be unique.
+    pub fn topological_sort(&self) -> Result<Vec<i32>, OrderError> {
+        let mut in_degrees: HashMap<i32, usize> =
+            self.pages.iter().map(|page| (*page, 0)).collect();
+        for after_page in self.successors.values().flatten() {
+            *in_degrees.get_mut(after_page).unwrap() += 1;
+        }
+
+        let mut ready: Vec<i32> = self
+            .pages
+            .iter()
+            .filter(|page| in_degrees[page] == 0)
+            .copied()
+            .collect();
+
+        let mut result = Vec::with_capacity(self.pages.len());
+        while let Some(page) = ready.pop() {
+            // Two pages without remaining predecessors can not reach each other
+            if let Some(other_page) = ready.last() {
+                return Err(OrderError::Ambiguous(*other_page, page));
+            }
+
+            result.push(page);
+            for after_page in &self.successors[&page] {
+                let in_degree = in_degrees.get_mut(after_page).unwrap();
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    ready.push(*after_page);
+                }
+            }
+        }
+
+        if result.len() < self.pages.len() {
+            return Err(OrderError::Cycle(self.find_cycle(&in_degrees)));
+        }
+
+        Ok(result)
+    }
+
+    /// Walks backwards from a page that is still waiting for a predecessor, every page on the way
+    /// is waiting as well, so a page is eventually repeated.
+    fn find_cycle(&self, in_degrees: &HashMap<i32, usize>) -> Vec<i32> {
+        let predecessor = |page: i32| {
+            self.pages
+                .iter()
+                .find(|other| in_degrees[*other] > 0 && self.successors[*other].contains(&page))
+                .copied()
+                .unwrap()
+        };
+
+        let mut path = vec![*self
+            .pages
+            .iter()
+            .find(|page| in_degrees[*page] > 0)
+            .unwrap()];
+        loop {
+            let page = predecessor(*path.last().unwrap());
+            if let Some(index) = path.iter().position(|other| *other == page) {
+                let mut cycle = path.split_off(index);
+                cycle.reverse();
+                return cycle;
+            }
+            path.push(page);
+        }
+    }
+}
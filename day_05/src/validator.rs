@@ -0,0 +1,185 @@
+// Advent of Code 2024 - Day 5
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::rule_graph::{OrderError, RuleGraph};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// A rule `before|after` broken by an update, `after` is printed at an earlier index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub before: i32,
+    pub after: i32,
+    pub before_index: usize,
+    pub after_index: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{} broken, {} at index {} but {} at index {}",
+            self.before, self.after, self.before, self.before_index, self.after, self.after_index
+        )
+    }
+}
+
+/// Moving `page` right behind `after`, or to the front if `after` is `None`. Applied in order, the
+/// moves turn the update into the corrected one, so `after` is either a page that stays or one
+/// that was moved before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub page: i32,
+    pub after: Option<i32>,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.after {
+            Some(after) => write!(f, "move {} after {after}", self.page),
+            None => write!(f, "move {} to the front", self.page),
+        }
+    }
+}
+
+/// Returns every rule the update breaks, an empty list means the update is valid.
+pub fn validate(ordering: &HashMap<i32, Vec<i32>>, update: &[i32]) -> Vec<Violation> {
+    let positions: HashMap<i32, usize> = update
+        .iter()
+        .enumerate()
+        .map(|(index, page)| (*page, index))
+        .collect();
+
+    let mut violations = Vec::new();
+    for (before_index, before) in update.iter().enumerate() {
+        for after in ordering.get(before).into_iter().flatten() {
+            if let Some(after_index) = positions.get(after) {
+                if *after_index < before_index {
+                    violations.push(Violation {
+                        before: *before,
+                        after: *after,
+                        before_index,
+                        after_index: *after_index,
+                    });
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Returns the fewest pages that have to be moved to make the update valid, in the order they
+/// have to be moved.
+///
+/// The pages that can stay are the longest subsequence of the update that is already in the
+/// corrected order, so every other page has to move.
+pub fn minimal_moves(
+    ordering: &HashMap<i32, Vec<i32>>,
+    update: &[i32],
+) -> Result<Vec<Move>, OrderError> {
+    let corrected = RuleGraph::for_update(ordering, update).topological_sort()?;
+    let targets: HashMap<i32, usize> = corrected
+        .iter()
+        .enumerate()
+        .map(|(index, page)| (*page, index))
+        .collect();
+    let sequence: Vec<usize> = update.iter().map(|page| targets[page]).collect();
+
+    let mut staying = vec![false; update.len()];
+    for index in longest_increasing_subsequence(&sequence) {
+        staying[index] = true;
+    }
+    let moving: HashSet<i32> = update
+        .iter()
+        .zip(&staying)
+        .filter(|(_, stays)| !**stays)
+        .map(|(page, _)| *page)
+        .collect();
+
+    // Going through the corrected update from the front, each page lands behind its predecessor
+    Ok(corrected
+        .iter()
+        .enumerate()
+        .filter(|(_, page)| moving.contains(page))
+        .map(|(to, page)| Move {
+            page: *page,
+            after: to.checked_sub(1).map(|index| corrected[index]),
+        })
+        .collect())
+}
+
+/// Returns the indices of a longest strictly increasing subsequence, in O(n log n).
+fn longest_increasing_subsequence(sequence: &[usize]) -> Vec<usize> {
+    // tails[k] is the index of the smallest value ending an increasing subsequence of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = vec![None; sequence.len()];
+
+    for (index, value) in sequence.iter().enumerate() {
+        let length = tails.partition_point(|tail| sequence[*tail] < *value);
+        previous[index] = length.checked_sub(1).map(|k| tails[k]);
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+
+    let mut result = Vec::with_capacity(tails.len());
+    let mut current = tails.last().copied();
+    while let Some(index) = current {
+        result.push(index);
+        current = previous[index];
+    }
+    result.reverse();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(update: &[i32], moves: &[Move]) -> Vec<i32> {
+        let mut update = update.to_vec();
+        for page_move in moves {
+            update.retain(|page| *page != page_move.page);
+            let index = page_move.after.map_or(0, |after| {
+                update.iter().position(|page| *page == after).unwrap() + 1
+            });
+            update.insert(index, page_move.page);
+        }
+        update
+    }
+
+    #[test]
+    fn moves_are_relative_to_other_pages() {
+        let ordering = HashMap::from([(47, vec![53]), (53, vec![13])]);
+        let update = [13, 53, 47];
+        let moves = minimal_moves(&ordering, &update).unwrap();
+        assert_eq!(moves.len(), 2);
+        assert_eq!(apply(&update, &moves), [47, 53, 13]);
+
+        let ordering = HashMap::from([(97, vec![13, 47, 29]), (47, vec![29]), (29, vec![13])]);
+        let update = [97, 13, 47, 29];
+        let moves = minimal_moves(&ordering, &update).unwrap();
+        assert_eq!(
+            moves.iter().map(Move::to_string).collect::<Vec<_>>(),
+            ["move 13 after 29"]
+        );
+        assert_eq!(apply(&update, &moves), [97, 47, 29, 13]);
+    }
+}
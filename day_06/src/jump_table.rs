@@ -0,0 +1,212 @@
+// Advent of Code 2024 - Day 6
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{Direction, Guard, PlayField, TurnPolicy};
+
+fn direction_index(direction: &Direction) -> usize {
+    match direction {
+        Direction::North => 0,
+        Direction::East => 1,
+        Direction::South => 2,
+        Direction::West => 3,
+    }
+}
+
+/// A jump table entry that was overwritten by [`JumpTable::add_obstruction`].
+struct Change {
+    cell: usize,
+    direction: usize,
+    jump: Option<usize>,
+}
+
+/// Lets the guard move from obstruction to obstruction instead of cell by cell.
+///
+/// For every cell and direction the table holds the cell where the guard stops in front of the
+/// next obstruction, or `None` if the guard walks off the play field.
+pub struct JumpTable {
+    width: usize,
+    height: usize,
+    obstructions: Vec<bool>,
+    jumps: Vec<[Option<usize>; 4]>,
+    /// Changes made by the obstruction added last, undone by `remove_obstruction`.
+    changes: Vec<Change>,
+    /// `seen[cell * 4 + direction] == generation` if the state was seen in the current walk.
+    seen: Vec<u32>,
+    generation: u32,
+}
+
+impl JumpTable {
+    pub fn new(play_field: &PlayField) -> Self {
+        let width = play_field.width as usize;
+        let height = play_field.height as usize;
+
+        let mut obstructions = vec![false; width * height];
+        for (x, y) in &play_field.obstructions {
+            obstructions[*y as usize * width + *x as usize] = true;
+        }
+
+        let mut jumps = vec![[None; 4]; width * height];
+        for y in 0..height {
+            let mut stop = None;
+            for x in 0..width {
+                let cell = y * width + x;
+                if obstructions[cell] {
+                    stop = Some(cell + 1);
+                } else {
+                    jumps[cell][direction_index(&Direction::West)] = stop;
+                }
+            }
+
+            let mut stop = None;
+            for x in (0..width).rev() {
+                let cell = y * width + x;
+                if obstructions[cell] {
+                    stop = cell.checked_sub(1);
+                } else {
+                    jumps[cell][direction_index(&Direction::East)] = stop;
+                }
+            }
+        }
+        for x in 0..width {
+            let mut stop = None;
+            for y in 0..height {
+                let cell = y * width + x;
+                if obstructions[cell] {
+                    stop = Some(cell + width);
+                } else {
+                    jumps[cell][direction_index(&Direction::North)] = stop;
+                }
+            }
+
+            let mut stop = None;
+            for y in (0..height).rev() {
+                let cell = y * width + x;
+                if obstructions[cell] {
+                    stop = cell.checked_sub(width);
+                } else {
+                    jumps[cell][direction_index(&Direction::South)] = stop;
+                }
+            }
+        }
+
+        JumpTable {
+            width,
+            height,
+            obstructions,
+            jumps,
+            changes: Vec::new(),
+            seen: vec![0; width * height * 4],
+            generation: 0,
+        }
+    }
+
+    fn set_jump(&mut self, cell: usize, direction: &Direction, jump: Option<usize>) {
+        let direction = direction_index(direction);
+        self.changes.push(Change {
+            cell,
+            direction,
+            jump: self.jumps[cell][direction],
+        });
+        self.jumps[cell][direction] = jump;
+    }
+
+    /// Adds an obstruction on an empty cell, only the jumps in its row and column are updated.
+    pub fn add_obstruction(&mut self, (x, y): (i32, i32)) {
+        let (x, y) = (x as usize, y as usize);
+        let obstruction = y * self.width + x;
+        assert!(!self.obstructions[obstruction]);
+        assert!(
+            self.changes.is_empty(),
+            "only one added obstruction is supported"
+        );
+
+        self.obstructions[obstruction] = true;
+
+        // Cells west of the obstruction walking east, up to the previous obstruction
+        for cell in (y * self.width..obstruction).rev() {
+            if self.obstructions[cell] {
+                break;
+            }
+            self.set_jump(cell, &Direction::East, Some(obstruction - 1));
+        }
+        for cell in obstruction + 1..(y + 1) * self.width {
+            if self.obstructions[cell] {
+                break;
+            }
+            self.set_jump(cell, &Direction::West, Some(obstruction + 1));
+        }
+        for cell in (x..obstruction).step_by(self.width).rev() {
+            if self.obstructions[cell] {
+                break;
+            }
+            self.set_jump(cell, &Direction::South, Some(obstruction - self.width));
+        }
+        for cell in (obstruction + self.width..self.width * self.height).step_by(self.width) {
+            if self.obstructions[cell] {
+                break;
+            }
+            self.set_jump(cell, &Direction::North, Some(obstruction + self.width));
+        }
+    }
+
+    /// Removes the obstruction added last and restores the jumps it changed.
+    pub fn remove_obstruction(&mut self, (x, y): (i32, i32)) {
+        let obstruction = y as usize * self.width + x as usize;
+        assert!(self.obstructions[obstruction]);
+
+        self.obstructions[obstruction] = false;
+        while let Some(change) = self.changes.pop() {
+            self.jumps[change.cell][change.direction] = change.jump;
+        }
+    }
+
+    /// If the guard walks in circles, returns the guard's starting position followed by every
+    /// cell where the guard turns, and the index in that path where the cycle starts. Only the
+    /// cells where the guard turns are visited.
+    pub fn find_loop(&mut self, guard: &Guard) -> Option<(Vec<(i32, i32)>, usize)> {
+        self.generation += 1;
+
+        let mut cell = guard.y as usize * self.width + guard.x as usize;
+        let mut direction = direction_index(&guard.direction);
+        let mut path = vec![cell];
+        // Every seen state together with its index in `path`
+        let mut states = Vec::new();
+        loop {
+            let stop = self.jumps[cell][direction]?;
+            let moved = stop != cell;
+            cell = stop;
+
+            let state = cell * 4 + direction;
+            if self.seen[state] == self.generation {
+                let (_, cycle_start) = states.iter().find(|(other, _)| *other == state).unwrap();
+                let path = path
+                    .into_iter()
+                    .map(|cell| ((cell % self.width) as i32, (cell / self.width) as i32))
+                    .collect();
+                return Some((path, *cycle_start));
+            }
+            self.seen[state] = self.generation;
+            if moved {
+                path.push(cell);
+            }
+            states.push((state, path.len() - 1));
+
+            direction = match guard.turn_policy {
+                TurnPolicy::Right => (direction + 1) % 4,
+                TurnPolicy::Reverse => (direction + 2) % 4,
+                TurnPolicy::Left => (direction + 3) % 4,
+            };
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// Advent of Code 2024 - Day 6
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{jump_table::JumpTable, Direction, Guard, PlayField};
+
+/// An obstruction that traps the guard in a loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    pub obstruction: (i32, i32),
+    /// The guard's starting position followed by every position where the guard turns.
+    pub path: Vec<(i32, i32)>,
+    /// Index in `path` of the first turning point of the cycle.
+    pub cycle_start: usize,
+}
+
+impl Loop {
+    /// The turning points of the cycle the guard ends up in, in walking order.
+    pub fn cycle(&self) -> &[(i32, i32)] {
+        &self.path[self.cycle_start..]
+    }
+
+    /// Renders the map with the obstruction marked `O` and the guard's path drawn with `|`, `-`
+    /// and `+`, like in the puzzle description.
+    pub fn render(&self, play_field: &PlayField, guard: &Guard) -> String {
+        let mut map: Vec<Vec<char>> = (0..play_field.height)
+            .map(|y| {
+                (0..play_field.width)
+                    .map(|x| {
+                        if play_field.obstructions.contains(&(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // The walk closes by returning to the start of the cycle
+        let closing = [*self.path.last().unwrap(), self.path[self.cycle_start]];
+        for segment in self.path.windows(2).chain([&closing[..]]) {
+            let [(x1, y1), (x2, y2)] = segment else {
+                unreachable!()
+            };
+            let line = if x1 == x2 { '|' } else { '-' };
+
+            for x in *x1.min(x2)..=*x1.max(x2) {
+                for y in *y1.min(y2)..=*y1.max(y2) {
+                    let cell = &mut map[y as usize][x as usize];
+                    *cell = match *cell {
+                        '.' => line,
+                        c if c == line => line,
+                        _ => '+',
+                    };
+                }
+            }
+        }
+
+        for (x, y) in &self.path[1..] {
+            map[*y as usize][*x as usize] = '+';
+        }
+
+        let (obstruction_x, obstruction_y) = self.obstruction;
+        map[obstruction_y as usize][obstruction_x as usize] = 'O';
+
+        map[guard.y as usize][guard.x as usize] = match guard.direction {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        };
+
+        map.into_iter()
+            .map(|row| row.into_iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+/// Tries an obstruction at every candidate position and returns those that trap the guard.
+pub fn find_loops(
+    play_field: &PlayField,
+    guard: &Guard,
+    candidates: impl IntoIterator<Item = (i32, i32)>,
+) -> Vec<Loop> {
+    let mut jump_table = JumpTable::new(play_field);
+    let mut result = Vec::new();
+
+    for obstruction in candidates {
+        jump_table.add_obstruction(obstruction);
+        if let Some((path, cycle_start)) = jump_table.find_loop(guard) {
+            result.push(Loop {
+                obstruction,
+                path,
+                cycle_start,
+            });
+        }
+        jump_table.remove_obstruction(obstruction);
+    }
+
+    result
+}
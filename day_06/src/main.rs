@@ -12,7 +12,11 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::{collections::HashSet, fs};
+mod jump_table;
+mod loops;
+mod simulation;
+
+use std::{collections::HashSet, env, fs};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum Direction {
@@ -22,11 +26,31 @@ enum Direction {
     West,
 }
 
+/// Which way a guard turns when facing an obstruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TurnPolicy {
+    Right,
+    Left,
+    Reverse,
+}
+
+impl TurnPolicy {
+    fn parse(policy: &str) -> TurnPolicy {
+        match policy {
+            "right" => TurnPolicy::Right,
+            "left" => TurnPolicy::Left,
+            "reverse" => TurnPolicy::Reverse,
+            policy => panic!("unknown turn policy: {policy}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Guard {
     x: i32,
     y: i32,
     direction: Direction,
+    turn_policy: TurnPolicy,
 }
 
 impl Guard {
@@ -50,11 +74,19 @@ impl Guard {
     }
 
     fn turn(&mut self) {
-        self.direction = match self.direction {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North,
+        let turns = match self.turn_policy {
+            TurnPolicy::Right => 1,
+            TurnPolicy::Reverse => 2,
+            TurnPolicy::Left => 3,
+        };
+
+        for _ in 0..turns {
+            self.direction = match self.direction {
+                Direction::North => Direction::East,
+                Direction::East => Direction::South,
+                Direction::South => Direction::West,
+                Direction::West => Direction::North,
+            }
         }
     }
 }
@@ -64,14 +96,14 @@ struct PlayField {
     width: i32,
     height: i32,
     obstructions: HashSet<(i32, i32)>,
-    guard: Guard,
+    guards: Vec<Guard>,
 }
 
 fn read_puzzle_input(filename: &str) -> PlayField {
     let contents = fs::read_to_string(filename).unwrap();
 
     let mut obstructions = HashSet::new();
-    let mut guard = None;
+    let mut guards = Vec::new();
 
     for (y, line) in contents.trim().split('\n').enumerate() {
         for (x, c) in line.chars().enumerate() {
@@ -80,15 +112,17 @@ fn read_puzzle_input(filename: &str) -> PlayField {
                     obstructions.insert((x as i32, y as i32));
                 }
                 '.' => (),
-                '^' => {
-                    assert!(guard.is_none());
-
-                    guard = Some(Guard {
-                        x: x as i32,
-                        y: y as i32,
-                        direction: Direction::North,
-                    })
-                }
+                '^' | '>' | 'v' | '<' => guards.push(Guard {
+                    x: x as i32,
+                    y: y as i32,
+                    direction: match c {
+                        '^' => Direction::North,
+                        '>' => Direction::East,
+                        'v' => Direction::South,
+                        _ => Direction::West,
+                    },
+                    turn_policy: TurnPolicy::Right,
+                }),
                 c => panic!("{c}"),
             }
         }
@@ -97,36 +131,35 @@ fn read_puzzle_input(filename: &str) -> PlayField {
     let height: i32 = contents.trim().split('\n').count() as i32;
     let width: i32 = contents.trim().split('\n').next().unwrap().chars().count() as i32;
 
+    assert!(!guards.is_empty(), "no guard in {filename}");
+
     PlayField {
         width,
         height,
         obstructions,
-        guard: guard.unwrap(),
+        guards,
     }
 }
 
 /// Returns a set of visited x and y positions if the guard will exit the play field, will return None if the guard is stuck in an infinite loop.
-fn get_visited_positions(play_field: &PlayField) -> Option<HashSet<(i32, i32)>> {
-    let mut guard = play_field.guard.clone();
+fn get_visited_positions(play_field: &PlayField, guard: &Guard) -> Option<HashSet<(i32, i32)>> {
+    let mut guard = guard.clone();
     let mut visited_positions = HashSet::new();
     let mut visited_positions_with_direction = HashSet::new();
 
     loop {
-        // Check if we are stuck
-        if visited_positions_with_direction.contains(&(guard.position(), guard.direction.clone())) {
+        // Check if we are stuck, this also catches a guard turning in place forever
+        if !visited_positions_with_direction.insert((guard.position(), guard.direction.clone())) {
             return None;
         }
+        visited_positions.insert(guard.position());
 
         let next_position = guard.peek_step();
         if play_field.obstructions.contains(&next_position) {
-            // TODO assert on 360 no-scope?
             guard.turn();
             continue;
         }
 
-        visited_positions.insert(guard.position());
-        visited_positions_with_direction.insert((guard.position(), guard.direction.clone()));
-
         let (next_x, next_y) = next_position;
         if next_x < 0 || next_y < 0 || next_x >= play_field.width || next_y >= play_field.height {
             break;
@@ -138,35 +171,159 @@ fn get_visited_positions(play_field: &PlayField) -> Option<HashSet<(i32, i32)>>
     Some(visited_positions)
 }
 
+/// Usage: `day_06 [--export <file>] [--turn <policy,...>] [--simulate] [FILE]`
+///
+/// Parts 1 and 2 follow the first guard on the map.
+///
+/// - `--export` writes every loop-causing obstruction and a map of the loop
+/// - `--turn` sets the turn policy (`right`, `left` or `reverse`) of each guard in reading order,
+///   a single policy applies to all guards
+/// - `--simulate` walks all guards together and reports collisions, visited cells and loops
 fn main() {
-    let mut play_field = read_puzzle_input("input.txt");
+    let mut export_filename = None;
+    let mut turn_policies = Vec::new();
+    let mut simulate = false;
+    let mut filename = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--export" => {
+                export_filename = Some(args.next().expect("--export requires a filename"))
+            }
+            "--turn" => {
+                let policies = args.next().expect("--turn requires a list of policies");
+                turn_policies = policies.split(',').map(TurnPolicy::parse).collect();
+            }
+            "--simulate" => simulate = true,
+            _ => filename = Some(arg),
+        }
+    }
 
-    assert!(!play_field
-        .obstructions
-        .contains(&play_field.guard.position()));
+    let is_puzzle = filename.is_none() && turn_policies.is_empty();
+    let mut play_field = read_puzzle_input(filename.as_deref().unwrap_or("input.txt"));
+
+    match turn_policies[..] {
+        [] => (),
+        [turn_policy] => {
+            for guard in &mut play_field.guards {
+                guard.turn_policy = turn_policy;
+            }
+        }
+        _ => {
+            assert_eq!(
+                turn_policies.len(),
+                play_field.guards.len(),
+                "one turn policy per guard"
+            );
+            for (guard, turn_policy) in play_field.guards.iter_mut().zip(turn_policies) {
+                guard.turn_policy = turn_policy;
+            }
+        }
+    }
+
+    if simulate {
+        for (index, report) in simulation::simulate(&play_field).iter().enumerate() {
+            println!(
+                "guard {index}: {} cells visited, {}",
+                report.visited.len(),
+                report.outcome
+            );
+        }
+    }
+
+    let guard = &play_field.guards[0];
+    assert!(!play_field.obstructions.contains(&guard.position()));
 
     // Part 1
 
-    let mut visited_nodes = get_visited_positions(&play_field).unwrap();
+    let Some(mut visited_nodes) = get_visited_positions(&play_field, guard) else {
+        println!("the guard never leaves");
+        return;
+    };
     println!("{}", visited_nodes.len());
-    assert_eq!(visited_nodes.len(), 5080);
+    if is_puzzle {
+        assert_eq!(visited_nodes.len(), 5080);
+    }
 
     // Part 2
-    // This might take some time to run in debug mode, run with `--release` for faster execution
-
     // Remove starting position, we are not allowed to place an obstruction here
-    visited_nodes.remove(&play_field.guard.position());
+    visited_nodes.remove(&guard.position());
 
-    let mut infinite_counter = 0;
-    for (x, y) in visited_nodes {
-        play_field.obstructions.insert((x, y));
-        if get_visited_positions(&play_field).is_none() {
-            infinite_counter += 1;
-        }
+    let mut candidates: Vec<(i32, i32)> = visited_nodes.into_iter().collect();
+    candidates.sort_by_key(|(x, y)| (*y, *x));
+
+    let loops = loops::find_loops(&play_field, guard, candidates);
+    let infinite_counter = loops.len();
 
-        play_field.obstructions.remove(&(x, y));
+    if let Some(filename) = export_filename {
+        let export: String = loops
+            .iter()
+            .map(|guard_loop| {
+                let (x, y) = guard_loop.obstruction;
+                let cycle: Vec<String> = guard_loop
+                    .cycle()
+                    .iter()
+                    .map(|(x, y)| format!("{x},{y}"))
+                    .collect();
+                format!(
+                    "O at {x},{y}, cycle {}\n{}\n",
+                    cycle.join(" -> "),
+                    guard_loop.render(&play_field, guard)
+                )
+            })
+            .collect();
+        fs::write(filename, export).unwrap();
     }
 
     println!("{infinite_counter}");
-    assert_eq!(infinite_counter, 1919);
+    if is_puzzle {
+        assert_eq!(infinite_counter, 1919);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_field(obstructions: &[(i32, i32)], turn_policy: TurnPolicy) -> PlayField {
+        PlayField {
+            width: 3,
+            height: 3,
+            obstructions: obstructions.iter().copied().collect(),
+            guards: vec![Guard {
+                x: 1,
+                y: 1,
+                direction: Direction::North,
+                turn_policy,
+            }],
+        }
+    }
+
+    #[test]
+    fn guard_reversing_between_obstructions_is_stuck() {
+        let play_field = play_field(&[(1, 0), (1, 2)], TurnPolicy::Reverse);
+        assert_eq!(
+            get_visited_positions(&play_field, &play_field.guards[0]),
+            None
+        );
+    }
+
+    #[test]
+    fn boxed_in_guard_is_stuck() {
+        let play_field = play_field(&[(1, 0), (2, 1), (1, 2), (0, 1)], TurnPolicy::Right);
+        assert_eq!(
+            get_visited_positions(&play_field, &play_field.guards[0]),
+            None
+        );
+    }
+
+    #[test]
+    fn guard_turning_once_leaves() {
+        let play_field = play_field(&[(1, 0)], TurnPolicy::Right);
+        assert_eq!(
+            get_visited_positions(&play_field, &play_field.guards[0]),
+            Some(HashSet::from([(1, 1), (2, 1)]))
+        );
+    }
 }
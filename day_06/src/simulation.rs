@@ -0,0 +1,161 @@
+// Advent of Code 2024 - Day 6
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{Direction, PlayField};
+use std::{collections::HashSet, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The guard walked off the play field.
+    Exited,
+    /// The guard is stuck in an infinite loop.
+    Loop,
+    /// The guard ran into another guard, either on the same cell or by swapping cells.
+    Collision { other: usize, position: (i32, i32) },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Exited => write!(f, "exited"),
+            Outcome::Loop => write!(f, "loop"),
+            Outcome::Collision {
+                other,
+                position: (x, y),
+            } => write!(f, "collided with guard {other} at {x},{y}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GuardReport {
+    pub visited: HashSet<(i32, i32)>,
+    pub outcome: Outcome,
+}
+
+/// Walks all guards together, one step or turn per guard and tick, until every guard has left,
+/// collided or is stuck in a loop. A guard stops walking once it left or collided, a guard in a
+/// loop keeps patrolling it, and can still collide, until all other guards are done.
+pub fn simulate(play_field: &PlayField) -> Vec<GuardReport> {
+    let mut guards = play_field.guards.clone();
+    let mut outcomes: Vec<Option<Outcome>> = vec![None; guards.len()];
+    let mut looping = vec![false; guards.len()];
+    let mut visited: Vec<HashSet<(i32, i32)>> = guards
+        .iter()
+        .map(|guard| HashSet::from([guard.position()]))
+        .collect();
+    let mut states: Vec<HashSet<((i32, i32), Direction)>> = guards
+        .iter()
+        .map(|guard| HashSet::from([(guard.position(), guard.direction.clone())]))
+        .collect();
+
+    while (0..guards.len()).any(|index| outcomes[index].is_none() && !looping[index]) {
+        let previous_positions: Vec<(i32, i32)> =
+            guards.iter().map(|guard| guard.position()).collect();
+
+        for (index, guard) in guards.iter_mut().enumerate() {
+            if outcomes[index].is_some() {
+                continue;
+            }
+
+            let next_position = guard.peek_step();
+            let (next_x, next_y) = next_position;
+            if play_field.obstructions.contains(&next_position) {
+                guard.turn();
+            } else if next_x < 0
+                || next_y < 0
+                || next_x >= play_field.width
+                || next_y >= play_field.height
+            {
+                outcomes[index] = Some(Outcome::Exited);
+            } else {
+                guard.take_step();
+                visited[index].insert(guard.position());
+            }
+        }
+
+        let walking: Vec<usize> = (0..guards.len())
+            .filter(|index| outcomes[*index].is_none())
+            .collect();
+
+        let mut collisions = Vec::new();
+        for (i, lhs) in walking.iter().enumerate() {
+            for rhs in &walking[i + 1..] {
+                let position = guards[*lhs].position();
+                let swapped = position == previous_positions[*rhs]
+                    && guards[*rhs].position() == previous_positions[*lhs];
+                if position == guards[*rhs].position() || swapped {
+                    collisions.push((*lhs, *rhs, position));
+                    collisions.push((*rhs, *lhs, guards[*rhs].position()));
+                }
+            }
+        }
+        for (index, other, position) in collisions {
+            outcomes[index].get_or_insert(Outcome::Collision { other, position });
+        }
+
+        for index in walking {
+            let guard = &guards[index];
+            if outcomes[index].is_none()
+                && !states[index].insert((guard.position(), guard.direction.clone()))
+            {
+                looping[index] = true;
+            }
+        }
+    }
+
+    visited
+        .into_iter()
+        .zip(outcomes)
+        .map(|(visited, outcome)| GuardReport {
+            visited,
+            outcome: outcome.unwrap_or(Outcome::Loop),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Guard, TurnPolicy};
+
+    fn guard(x: i32, y: i32) -> Guard {
+        Guard {
+            x,
+            y,
+            direction: Direction::North,
+            turn_policy: TurnPolicy::Right,
+        }
+    }
+
+    #[test]
+    fn guard_collides_with_a_looping_guard() {
+        let play_field = PlayField {
+            width: 6,
+            height: 17,
+            obstructions: HashSet::from([(1, 0), (4, 1), (3, 4), (0, 3)]),
+            guards: vec![guard(1, 2), guard(2, 16)],
+        };
+
+        let reports = simulate(&play_field);
+        assert!(matches!(
+            reports[1].outcome,
+            Outcome::Collision { other: 0, .. }
+        ));
+        assert!(matches!(
+            reports[0].outcome,
+            Outcome::Collision { other: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+// Advent of Code 2024 - Day 7
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{
+    number::Number,
+    operators::{Add, Concat, Inverse, Mul, Operator},
+    solver, Statement,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+/// Returns the number of distinct operator sequences that make the equation true.
+///
+/// Works backwards like [`solver::solve`], but explores every operator and memoizes the number of
+/// ways each intermediate result can be produced by the leading values.
+pub fn count_solutions(statement: &Statement, operators: &[Box<dyn Operator>]) -> u128 {
+    let mut cache = HashMap::new();
+    count_from(&statement.result, &statement.values, operators, &mut cache)
+}
+
+fn count_from(
+    result: &Number,
+    values: &[Number],
+    operators: &[Box<dyn Operator>],
+    cache: &mut HashMap<(Number, usize), u128>,
+) -> u128 {
+    let Some((last, rest)) = values.split_last() else {
+        return 0;
+    };
+    if rest.is_empty() {
+        return u128::from(result == last);
+    }
+
+    let key = (result.to_owned(), values.len());
+    if let Some(count) = cache.get(&key) {
+        return *count;
+    }
+
+    let mut count = 0;
+    for operator in operators {
+        count += match operator.inverse(result, last) {
+            Inverse::Value(lhs) => count_from(&lhs, rest, operators, cache),
+            Inverse::Impossible => 0,
+            Inverse::Unknown => solver::evaluate(rest, operators)
+                .into_iter()
+                .filter(|(lhs, _)| operator.apply(lhs, last).as_ref() == Some(result))
+                .count() as u128,
+        };
+    }
+
+    cache.insert(key, count);
+    count
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bucket {
+    pub equations: usize,
+    /// Solvable with `+` and `*`.
+    pub solvable: usize,
+    /// Only solvable when `||` is added.
+    pub only_with_concat: usize,
+    /// Total number of operator sequences over `+`, `*` and `||` that solve the equations.
+    pub solutions: u128,
+}
+
+impl Bucket {
+    fn add(&mut self, other: &Bucket) {
+        self.equations += other.equations;
+        self.solvable += other.solvable;
+        self.only_with_concat += other.only_with_concat;
+        self.solutions += other.solutions;
+    }
+}
+
+/// Difficulty statistics for a file of equations, overall and grouped by operand count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub total: Bucket,
+    pub by_operand_count: BTreeMap<usize, Bucket>,
+}
+
+impl Statistics {
+    pub fn new(statements: &[Statement]) -> Self {
+        let without_concat: Vec<Box<dyn Operator>> = vec![Box::new(Add), Box::new(Mul)];
+        let with_concat: Vec<Box<dyn Operator>> =
+            vec![Box::new(Add), Box::new(Mul), Box::new(Concat)];
+
+        let mut statistics = Statistics::default();
+        for statement in statements {
+            let solvable = solver::solve(statement, &without_concat).is_some();
+            let solutions = count_solutions(statement, &with_concat);
+
+            let bucket = Bucket {
+                equations: 1,
+                solvable: usize::from(solvable),
+                only_with_concat: usize::from(!solvable && solutions > 0),
+                solutions,
+            };
+            statistics.total.add(&bucket);
+            statistics
+                .by_operand_count
+                .entry(statement.values.len())
+                .or_default()
+                .add(&bucket);
+        }
+
+        statistics
+    }
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * count as f64 / total as f64
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = &self.total;
+        writeln!(f, "equations: {}", total.equations)?;
+        writeln!(
+            f,
+            "solvable with + and *: {} ({:.1}%)",
+            total.solvable,
+            percentage(total.solvable, total.equations)
+        )?;
+        writeln!(
+            f,
+            "solvable only with ||: {} ({:.1}%)",
+            total.only_with_concat,
+            percentage(total.only_with_concat, total.equations)
+        )?;
+        writeln!(f)?;
+        writeln!(f, "operands  equations  solvable  only ||  solutions")?;
+        for (operand_count, bucket) in &self.by_operand_count {
+            writeln!(
+                f,
+                "{operand_count:>8}  {:>9}  {:>8}  {:>7}  {:>9}",
+                bucket.equations, bucket.solvable, bucket.only_with_concat, bucket.solutions
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -12,12 +12,26 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::fs;
+mod analytics;
+mod number;
+mod operators;
+mod solver;
+
+use number::Number;
+use operators::{Add, Concat, Mul, Operator};
+use std::{env, fs};
 
 #[derive(Debug)]
 struct Statement {
-    result: i64,
-    values: Vec<i64>,
+    result: Number,
+    values: Vec<Number>,
+}
+
+/// Values too large for `i128` require the `bigint` feature.
+fn parse_number(value: &str) -> Number {
+    value
+        .parse()
+        .unwrap_or_else(|e| panic!("invalid value {value}: {e}"))
 }
 
 fn read_puzzle_input(filename: &str) -> Vec<Statement> {
@@ -27,112 +41,114 @@ fn read_puzzle_input(filename: &str) -> Vec<Statement> {
     for line in contents.trim().split('\n') {
         let (a, b) = line.split_once(": ").unwrap();
         result.push(Statement {
-            result: a.parse().unwrap(),
-            values: b.split(' ').map(|v| v.parse().unwrap()).collect(),
+            result: parse_number(a),
+            values: b.split(' ').map(parse_number).collect(),
         });
     }
 
     result
 }
 
-fn calc(numbers: &Vec<i64>, operators: &[char]) -> i64 {
-    assert_eq!(numbers.len(), operators.len() + 1);
-
-    // Just insert a + in the begining, this can probably be avoided to be more efficient
-    let mut operators = operators.to_owned();
-    operators.insert(0, '+');
-    let mut result = 0;
-    for (op, value) in operators.iter().zip(numbers) {
-        match *op {
-            '+' => result += value,
-            '*' => result *= value,
-
-            // this operation can probably be done in a more efficient way
-            '|' => {
-                result = (result.to_string() + value.to_string().as_str())
-                    .parse()
-                    .unwrap()
+/// Returns the sum of the results of all equations that can be made true with the operators.
+fn total_calibration_result(
+    expressions: &[Statement],
+    operators: &[Box<dyn Operator>],
+    show: bool,
+) -> Number {
+    let mut result = number::from_i64(0);
+    for v in expressions {
+        if let Some(solution) = solver::solve(v, operators) {
+            if show {
+                println!("{}", solver::format_expression(v, &solution));
             }
-            _ => unreachable!(),
+            result = number::add(&result, &v.result).expect("total overflows");
         }
     }
 
     result
 }
 
-enum Part {
-    One,
-    Two,
-}
-
-fn get_mutations(len: usize, part: Part) -> Vec<Vec<char>> {
-    let start: Vec<char> = vec!['+'; len];
-
-    let mut result = Vec::new();
-    let mut current = start.clone();
-    loop {
-        result.push(current.clone());
-
-        let mut carry_over = true;
-        let mut new = Vec::new();
-        for c in current.iter() {
-            if carry_over {
-                let (next_char, next_spill_over) = match part {
-                    Part::One => match *c {
-                        '+' => ('*', false),
-                        '*' => ('+', true),
-                        _ => unreachable!(),
-                    },
-                    Part::Two => match *c {
-                        '+' => ('*', false),
-                        '*' => ('|', false),
-                        '|' => ('+', true),
-                        _ => unreachable!(),
-                    },
-                };
-                carry_over = next_spill_over;
-                new.push(next_char);
-            } else {
-                new.push(*c);
+/// Usage: `day_07 [--show] [--count] [--stats] [--operators <name,...>] [FILE]`
+///
+/// `--show` prints the expression found for every equation that can be made true. `--operators`
+/// solves with another operator set instead of parts 1 and 2, available operators are `add`,
+/// `mul`, `concat`, `sub`, `xor` and `pow`. `--count` prints the number of operator sequences
+/// that solve each equation, with the part 2 operators unless `--operators` is given. `--stats`
+/// prints how many equations require `||`, grouped by operand count.
+fn main() {
+    let mut show = false;
+    let mut count = false;
+    let mut stats = false;
+    let mut operator_set = None;
+    let mut filename = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--show" => show = true,
+            "--count" => count = true,
+            "--stats" => stats = true,
+            "--operators" => {
+                let names = args
+                    .next()
+                    .expect("--operators requires a list of operators");
+                let operators: Vec<Box<dyn Operator>> = names
+                    .split(',')
+                    .map(|name| {
+                        operators::builtin(name)
+                            .unwrap_or_else(|| panic!("unknown operator {name}"))
+                    })
+                    .collect();
+                operator_set = Some(operators);
             }
+            _ => filename = Some(arg),
         }
+    }
 
-        current = new;
-
-        if current == start {
-            break;
+    let is_puzzle = filename.is_none();
+    let expressions = read_puzzle_input(filename.as_deref().unwrap_or("input.txt"));
+
+    if count {
+        let operators: Vec<Box<dyn Operator>> =
+            operator_set.unwrap_or_else(|| vec![Box::new(Add), Box::new(Mul), Box::new(Concat)]);
+        for v in &expressions {
+            let values: Vec<String> = v.values.iter().map(Number::to_string).collect();
+            println!(
+                "{}: {} => {}",
+                v.result,
+                values.join(" "),
+                analytics::count_solutions(v, &operators)
+            );
         }
+        return;
     }
 
-    result
-}
+    if stats {
+        print!("{}", analytics::Statistics::new(&expressions));
+        return;
+    }
 
-fn main() {
-    let expressions = read_puzzle_input("input.txt");
-
-    let mut result = 0;
-    for v in &expressions {
-        for m in get_mutations(v.values.len() - 1, Part::One) {
-            if v.result == calc(&v.values, &m) {
-                result += v.result;
-                break;
-            }
-        }
+    if let Some(operators) = operator_set {
+        let result = total_calibration_result(&expressions, &operators, show);
+        println!("{result}");
+        return;
     }
 
-    println!("{result}");
-    assert_eq!(result, 4364915411363);
+    // Part 1
+    let operators: Vec<Box<dyn Operator>> = vec![Box::new(Add), Box::new(Mul)];
+    let result = total_calibration_result(&expressions, &operators, show);
 
-    let mut result = 0;
-    for v in expressions {
-        for m in get_mutations(v.values.len() - 1, Part::Two) {
-            if v.result == calc(&v.values, &m) {
-                result += v.result;
-                break;
-            }
-        }
+    println!("{result}");
+    if is_puzzle {
+        assert_eq!(result, number::from_i64(4364915411363));
     }
 
+    // Part 2
+    let operators: Vec<Box<dyn Operator>> = vec![Box::new(Add), Box::new(Mul), Box::new(Concat)];
+    let result = total_calibration_result(&expressions, &operators, show);
+
     println!("{result}");
-    assert_eq!(result, 38322057216320);
+    if is_puzzle {
+        assert_eq!(result, number::from_i64(38322057216320));
+    }
 }
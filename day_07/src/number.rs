@@ -0,0 +1,129 @@
+// Advent of Code 2024 - Day 7
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Arithmetic on equation values. Every operation returns `None` instead of overflowing, so an
+//! equation that only works with an overflowing intermediate result has no solution.
+//!
+//! Values are `i128` by default, build with `--features bigint` for arbitrarily large values.
+
+#[cfg(not(feature = "bigint"))]
+mod backend {
+    pub type Number = i128;
+
+    pub fn from_i64(value: i64) -> Number {
+        Number::from(value)
+    }
+
+    pub fn is_zero(value: &Number) -> bool {
+        *value == 0
+    }
+
+    pub fn is_negative(value: &Number) -> bool {
+        *value < 0
+    }
+
+    pub fn add(lhs: &Number, rhs: &Number) -> Option<Number> {
+        lhs.checked_add(*rhs)
+    }
+
+    pub fn sub(lhs: &Number, rhs: &Number) -> Option<Number> {
+        lhs.checked_sub(*rhs)
+    }
+
+    pub fn mul(lhs: &Number, rhs: &Number) -> Option<Number> {
+        lhs.checked_mul(*rhs)
+    }
+
+    /// Returns `lhs / rhs` if `rhs` divides `lhs`.
+    pub fn div_exact(lhs: &Number, rhs: &Number) -> Option<Number> {
+        (lhs.checked_rem(*rhs)? == 0).then(|| lhs / rhs)
+    }
+
+    pub fn pow(lhs: &Number, rhs: &Number) -> Option<Number> {
+        lhs.checked_pow(u32::try_from(*rhs).ok()?)
+    }
+
+    pub fn xor(lhs: &Number, rhs: &Number) -> Number {
+        lhs ^ rhs
+    }
+}
+
+#[cfg(feature = "bigint")]
+mod backend {
+    use num_bigint::BigInt;
+    use num_traits::{Signed, ToPrimitive, Zero};
+
+    pub type Number = BigInt;
+
+    /// Results of `pow` larger than this are rejected instead of exhausting memory.
+    const MAX_POW_BITS: u64 = 1 << 20;
+
+    pub fn from_i64(value: i64) -> Number {
+        Number::from(value)
+    }
+
+    pub fn is_zero(value: &Number) -> bool {
+        value.is_zero()
+    }
+
+    pub fn is_negative(value: &Number) -> bool {
+        value.is_negative()
+    }
+
+    pub fn add(lhs: &Number, rhs: &Number) -> Option<Number> {
+        Some(lhs + rhs)
+    }
+
+    pub fn sub(lhs: &Number, rhs: &Number) -> Option<Number> {
+        Some(lhs - rhs)
+    }
+
+    pub fn mul(lhs: &Number, rhs: &Number) -> Option<Number> {
+        Some(lhs * rhs)
+    }
+
+    /// Returns `lhs / rhs` if `rhs` divides `lhs`.
+    pub fn div_exact(lhs: &Number, rhs: &Number) -> Option<Number> {
+        if rhs.is_zero() {
+            return None;
+        }
+        (lhs % rhs).is_zero().then(|| lhs / rhs)
+    }
+
+    pub fn pow(lhs: &Number, rhs: &Number) -> Option<Number> {
+        let exponent = rhs.to_u32()?;
+        if lhs.bits().saturating_mul(u64::from(exponent)) > MAX_POW_BITS {
+            return None;
+        }
+        Some(lhs.pow(exponent))
+    }
+
+    pub fn xor(lhs: &Number, rhs: &Number) -> Number {
+        lhs ^ rhs
+    }
+}
+
+pub use backend::*;
+
+/// Returns the smallest power of ten larger than `value`, but at least 10.
+pub fn magnitude(value: &Number) -> Option<Number> {
+    let ten = from_i64(10);
+    let mut magnitude = from_i64(10);
+    while magnitude <= *value {
+        magnitude = mul(&magnitude, &ten)?;
+    }
+
+    Some(magnitude)
+}
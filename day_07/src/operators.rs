@@ -0,0 +1,171 @@
+// Advent of Code 2024 - Day 7
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::number::{self, Number};
+
+/// Result of undoing an operator, see [`Operator::inverse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inverse {
+    /// The only `lhs` that gives the result.
+    Value(Number),
+    /// No `lhs` gives the result.
+    Impossible,
+    /// The operator can not be undone for these values, the solver evaluates forwards instead.
+    Unknown,
+}
+
+impl From<Option<Number>> for Inverse {
+    fn from(value: Option<Number>) -> Self {
+        value.map_or(Inverse::Impossible, Inverse::Value)
+    }
+}
+
+/// A binary operator, equations are evaluated left to right.
+pub trait Operator {
+    fn symbol(&self) -> &str;
+
+    /// Returns `lhs <op> rhs`, or `None` if it is undefined or overflows.
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number>;
+
+    /// Returns the `lhs` for which `lhs <op> rhs == result`, used by the solver to prune.
+    fn inverse(&self, _result: &Number, _rhs: &Number) -> Inverse {
+        Inverse::Unknown
+    }
+}
+
+pub struct Add;
+
+impl Operator for Add {
+    fn symbol(&self) -> &str {
+        "+"
+    }
+
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number> {
+        number::add(lhs, rhs)
+    }
+
+    fn inverse(&self, result: &Number, rhs: &Number) -> Inverse {
+        number::sub(result, rhs).into()
+    }
+}
+
+pub struct Mul;
+
+impl Operator for Mul {
+    fn symbol(&self) -> &str {
+        "*"
+    }
+
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number> {
+        number::mul(lhs, rhs)
+    }
+
+    fn inverse(&self, result: &Number, rhs: &Number) -> Inverse {
+        if number::is_zero(rhs) {
+            // Every lhs gives 0
+            Inverse::Unknown
+        } else {
+            number::div_exact(result, rhs).into()
+        }
+    }
+}
+
+/// Appends the digits of `rhs` to `lhs`, e.g. `12 || 345 = 12345`.
+pub struct Concat;
+
+impl Operator for Concat {
+    fn symbol(&self) -> &str {
+        "||"
+    }
+
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number> {
+        if number::is_negative(lhs) || number::is_negative(rhs) {
+            return None;
+        }
+        number::add(&number::mul(lhs, &number::magnitude(rhs)?)?, rhs)
+    }
+
+    fn inverse(&self, result: &Number, rhs: &Number) -> Inverse {
+        if result < rhs || number::is_negative(rhs) {
+            return Inverse::Impossible;
+        }
+
+        // If the magnitude overflows, so would every concatenation with rhs
+        let Some(magnitude) = number::magnitude(rhs) else {
+            return Inverse::Impossible;
+        };
+        number::sub(result, rhs)
+            .and_then(|prefix| number::div_exact(&prefix, &magnitude))
+            .into()
+    }
+}
+
+pub struct Sub;
+
+impl Operator for Sub {
+    fn symbol(&self) -> &str {
+        "-"
+    }
+
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number> {
+        number::sub(lhs, rhs)
+    }
+
+    fn inverse(&self, result: &Number, rhs: &Number) -> Inverse {
+        number::add(result, rhs).into()
+    }
+}
+
+pub struct Xor;
+
+impl Operator for Xor {
+    fn symbol(&self) -> &str {
+        "^"
+    }
+
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number> {
+        Some(number::xor(lhs, rhs))
+    }
+
+    fn inverse(&self, result: &Number, rhs: &Number) -> Inverse {
+        Inverse::Value(number::xor(result, rhs))
+    }
+}
+
+/// Raises `lhs` to the power of `rhs`, there is no inverse so it is always evaluated forwards.
+pub struct Pow;
+
+impl Operator for Pow {
+    fn symbol(&self) -> &str {
+        "**"
+    }
+
+    fn apply(&self, lhs: &Number, rhs: &Number) -> Option<Number> {
+        number::pow(lhs, rhs)
+    }
+}
+
+/// Looks up one of the operators shipped with this program by name.
+pub fn builtin(name: &str) -> Option<Box<dyn Operator>> {
+    match name {
+        "add" => Some(Box::new(Add)),
+        "mul" => Some(Box::new(Mul)),
+        "concat" => Some(Box::new(Concat)),
+        "sub" => Some(Box::new(Sub)),
+        "xor" => Some(Box::new(Xor)),
+        "pow" => Some(Box::new(Pow)),
+        _ => None,
+    }
+}
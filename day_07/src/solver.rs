@@ -0,0 +1,96 @@
+// Advent of Code 2024 - Day 7
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{
+    number::Number,
+    operators::{Inverse, Operator},
+    Statement,
+};
+
+/// Returns the operators, in order, that make the equation true.
+///
+/// Works backwards from the result, an operator is only tried if its inverse gives a candidate for
+/// the preceding values, e.g. `*` requires the result to be divisible by the last value. Operators
+/// without an inverse are handled by evaluating the preceding values forwards.
+pub fn solve<'a>(
+    statement: &Statement,
+    operators: &'a [Box<dyn Operator>],
+) -> Option<Vec<&'a dyn Operator>> {
+    solve_from(&statement.result, &statement.values, operators)
+}
+
+fn solve_from<'a>(
+    result: &Number,
+    values: &[Number],
+    operators: &'a [Box<dyn Operator>],
+) -> Option<Vec<&'a dyn Operator>> {
+    let (last, rest) = values.split_last()?;
+    if rest.is_empty() {
+        return (result == last).then(Vec::new);
+    }
+
+    for operator in operators {
+        let solution = match operator.inverse(result, last) {
+            Inverse::Value(lhs) => solve_from(&lhs, rest, operators),
+            Inverse::Impossible => None,
+            Inverse::Unknown => evaluate(rest, operators)
+                .into_iter()
+                .find(|(lhs, _)| operator.apply(lhs, last).as_ref() == Some(result))
+                .map(|(_, solution)| solution),
+        };
+
+        if let Some(mut solution) = solution {
+            solution.push(operator.as_ref());
+            return Some(solution);
+        }
+    }
+
+    None
+}
+
+/// Evaluates every combination of operators, returns each result with the operators producing it.
+pub fn evaluate<'a>(
+    values: &[Number],
+    operators: &'a [Box<dyn Operator>],
+) -> Vec<(Number, Vec<&'a dyn Operator>)> {
+    let Some((last, rest)) = values.split_last() else {
+        return Vec::new();
+    };
+    if rest.is_empty() {
+        return vec![(last.to_owned(), Vec::new())];
+    }
+
+    let mut results = Vec::new();
+    for (lhs, solution) in evaluate(rest, operators) {
+        for operator in operators {
+            if let Some(result) = operator.apply(&lhs, last) {
+                let mut solution = solution.clone();
+                solution.push(operator.as_ref());
+                results.push((result, solution));
+            }
+        }
+    }
+
+    results
+}
+
+/// Formats the equation as it is evaluated, left to right, e.g. `3267 = 81 * 40 + 27`.
+pub fn format_expression(statement: &Statement, operators: &[&dyn Operator]) -> String {
+    let mut expression = format!("{} = {}", statement.result, statement.values[0]);
+    for (operator, value) in operators.iter().zip(&statement.values[1..]) {
+        expression += &format!(" {} {value}", operator.symbol());
+    }
+
+    expression
+}
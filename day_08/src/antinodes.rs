@@ -0,0 +1,119 @@
+// Advent of Code 2024 - Day 8
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{Antenna, Map};
+use std::{collections::HashSet, fmt};
+
+/// Which points in line with a pair of antennas are antinodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Harmonic {
+    /// Points outside the pair where the distances to the antennas have the ratio `near:far`,
+    /// `1:2` gives the antinodes of part 1.
+    Ratio { near: i32, far: i32 },
+    /// Every grid point on the line through the antennas, as in part 2.
+    Collinear,
+}
+
+impl Harmonic {
+    /// Parses `all` or a ratio such as `1:3`.
+    pub fn parse(spec: &str) -> Option<Harmonic> {
+        if spec == "all" {
+            return Some(Harmonic::Collinear);
+        }
+
+        let (near, far) = spec.split_once(':')?;
+        let near: i32 = near.parse().ok()?;
+        let far: i32 = far.parse().ok()?;
+        (0 < near && near < far).then_some(Harmonic::Ratio { near, far })
+    }
+}
+
+impl fmt::Display for Harmonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Harmonic::Ratio { near, far } => write!(f, "{near}:{far}"),
+            Harmonic::Collinear => write!(f, "all"),
+        }
+    }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Returns the antinodes of a pair of antennas that lie on the map.
+pub fn pair_antinodes(
+    map: &Map,
+    first: &Antenna,
+    second: &Antenna,
+    harmonic: &Harmonic,
+) -> Vec<(i32, i32)> {
+    let delta_x = second.x - first.x;
+    let delta_y = second.y - first.y;
+    if delta_x == 0 && delta_y == 0 {
+        return Vec::new();
+    }
+
+    let mut antinodes = Vec::new();
+    match harmonic {
+        Harmonic::Ratio { near, far } => {
+            // The antinode beyond the second antenna is `near / (far - near)` deltas away from it,
+            // it only counts if that lands on a grid point
+            let distance = far - near;
+            if (delta_x * near) % distance == 0 && (delta_y * near) % distance == 0 {
+                let step_x = delta_x * near / distance;
+                let step_y = delta_y * near / distance;
+                antinodes.push((second.x + step_x, second.y + step_y));
+                antinodes.push((first.x - step_x, first.y - step_y));
+            }
+        }
+        Harmonic::Collinear => {
+            // Reduce the delta to the smallest step between grid points on the line
+            let divisor = gcd(delta_x, delta_y);
+            let step_x = delta_x / divisor;
+            let step_y = delta_y / divisor;
+            for (step_x, step_y) in [(step_x, step_y), (-step_x, -step_y)] {
+                let (mut x, mut y) = (first.x, first.y);
+                while map.contains(x, y) {
+                    antinodes.push((x, y));
+                    x += step_x;
+                    y += step_y;
+                }
+            }
+        }
+    }
+
+    antinodes.retain(|(x, y)| map.contains(*x, *y));
+    antinodes
+}
+
+/// Returns the antinodes of all pairs of the antennas, which share a frequency.
+pub fn frequency_antinodes(
+    map: &Map,
+    antennas: &[&Antenna],
+    harmonic: &Harmonic,
+) -> HashSet<(i32, i32)> {
+    let mut antinodes = HashSet::new();
+    for (index, first) in antennas.iter().enumerate() {
+        for second in antennas.iter().skip(index + 1) {
+            antinodes.extend(pair_antinodes(map, first, second, harmonic));
+        }
+    }
+
+    antinodes
+}
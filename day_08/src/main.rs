@@ -12,161 +12,102 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::{collections::HashSet, fs};
+mod antinodes;
+mod map;
+mod report;
 
-#[derive(Debug)]
-struct Antenna {
-    frequency: char,
-    x: i32,
-    y: i32,
-}
-
-#[derive(Debug)]
-struct Map {
-    width: i32,
-    height: i32,
-    antennas: Vec<Antenna>,
-}
+use antinodes::Harmonic;
+use map::{Antenna, Map};
+use std::{collections::HashSet, env, fs};
 
 fn read_puzzle_input(filename: &str) -> Map {
     let contents = fs::read_to_string(filename).unwrap();
 
-    let mut antennas = Vec::new();
-
-    for (y, line) in contents.split('\n').enumerate() {
-        for (x, frequency) in line.chars().enumerate() {
-            match frequency {
-                '.' => (),
-                'a'..='z' | 'A'..='Z' | '0'..='9' => {
-                    antennas.push(Antenna {
-                        frequency,
-                        x: x as i32,
-                        y: y as i32,
-                    });
-                }
-                other => panic!("Unexpected char {}", other),
-            }
-        }
-    }
-
-    let height: i32 = contents.trim().split('\n').count() as i32;
-    let width: i32 = contents.trim().split('\n').next().unwrap().chars().count() as i32;
-
-    Map {
-        width,
-        height,
-        antennas,
-    }
+    Map::parse(&contents).unwrap_or_else(|e| panic!("{filename}: {e}"))
 }
 
-// Antinodes for part 1
-fn get_antinodes(map: &Map, first: &Antenna, second: &Antenna) -> Vec<(i32, i32)> {
-    let delta_x = second.x - first.x;
-    let delta_y = second.y - first.y;
-
-    // Possible antinodes from the perspective of the first antenna
-    let possible_first = HashSet::from([
-        (
-            (first.x - delta_x, first.y - delta_y),
-            (first.x + 2 * delta_x, first.y + 2 * delta_y),
-        ),
-        (
-            (first.x + delta_x, first.y + delta_y),
-            (first.x - 2 * delta_x, first.y - 2 * delta_y),
-        ),
-    ]);
-
-    // Possible antinodes from the perspective of the second antenna
-    let possible_second = HashSet::from([
-        (
-            (second.x + 2 * delta_x, second.y + 2 * delta_y),
-            (second.x - delta_x, second.y - delta_y),
-        ),
-        (
-            (second.x - 2 * delta_x, second.y - 2 * delta_y),
-            (second.x + delta_x, second.y + delta_y),
-        ),
-    ]);
-
-    // The intersection of these sets gives us the antinodes that fulfills the criteras for both antennas
-    assert_eq!(possible_first.intersection(&possible_second).count(), 1);
-
-    let ((x1, y1), (x2, y2)) = possible_first
-        .intersection(&possible_second)
-        .last()
-        .unwrap();
-
-    [(*x1, *y1), (*x2, *y2)]
+/// Counts the antinodes of the selected frequencies, or of all frequencies without a filter.
+fn count_antinodes(
+    map: &Map,
+    harmonic: &Harmonic,
+    frequencies: Option<&str>,
+    report: bool,
+    render: bool,
+) -> usize {
+    let reports = report::frequency_reports(map, harmonic);
+    let selected: Vec<&report::FrequencyReport> = reports
         .iter()
-        .filter(|(x, y)| 0 <= *x && *x < map.width && 0 <= *y && *y < map.height)
-        .map(|(x, y)| (*x, *y))
-        .collect()
-}
-
-// Antinodes for part 2
-fn get_antinodes_2(map: &Map, first: &Antenna, second: &Antenna) -> Vec<(i32, i32)> {
-    let delta_x = second.x - first.x;
-    let delta_y = second.y - first.y;
-
-    let mut antinodes = Vec::new();
+        .filter(|report| frequencies.is_none_or(|filter| filter.contains(report.frequency)))
+        .collect();
 
-    // Go backwards
-    let mut x = first.x;
-    let mut y = first.y;
-    while 0 <= x && x < map.width && 0 <= y && y < map.height {
-        antinodes.push((x, y));
-        x -= delta_x;
-        y -= delta_y;
+    if report {
+        for frequency_report in &selected {
+            println!("{frequency_report}");
+        }
     }
-
-    // Go forwards
-    let mut x = first.x + delta_x;
-    let mut y = first.y + delta_y;
-    while 0 <= x && x < map.width && 0 <= y && y < map.height {
-        antinodes.push((x, y));
-        x += delta_x;
-        y += delta_y;
+    if render {
+        print!("{}", report::render(map, &selected));
     }
 
-    antinodes
+    let antinodes: HashSet<(i32, i32)> = selected
+        .iter()
+        .flat_map(|report| report.antinodes.iter().copied())
+        .collect();
+    antinodes.len()
 }
 
+/// Usage: `day_08 [--harmonic <1:2|1:3|...|all>] [--frequency <chars>] [--report] [--render] [FILE]`
+///
+/// `--harmonic` counts the antinodes for another harmonic instead of parts 1 and 2.
+/// `--frequency` only counts the antinodes of the given frequencies, e.g. `--frequency aA0`.
+/// `--report` prints the antinodes of each frequency and `--render` draws them on the map.
 fn main() {
-    let map = read_puzzle_input("input.txt");
-
-    // Part 1
-    let mut unique_locations = HashSet::new();
-
-    for (index, first_antenna) in map.antennas.iter().enumerate() {
-        for second_antenna in map.antennas.iter().skip(index + 1) {
-            if first_antenna.frequency == second_antenna.frequency {
-                let antinodes = get_antinodes(&map, first_antenna, second_antenna);
-                for antinode in antinodes {
-                    unique_locations.insert(antinode);
-                }
+    let mut harmonic = None;
+    let mut frequencies = None;
+    let mut report = false;
+    let mut render = false;
+    let mut filename = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--harmonic" => {
+                let spec = args.next().expect("--harmonic requires a ratio or all");
+                harmonic = Some(
+                    Harmonic::parse(&spec).unwrap_or_else(|| panic!("invalid harmonic {spec}")),
+                );
             }
+            "--frequency" => {
+                frequencies = Some(args.next().expect("--frequency requires frequencies"));
+            }
+            "--report" => report = true,
+            "--render" => render = true,
+            _ => filename = Some(arg),
         }
     }
 
-    let result = unique_locations.len();
-    assert_eq!(result, 361);
-    println!("{result}");
+    let is_puzzle = filename.is_none() && frequencies.is_none();
+    let map = read_puzzle_input(filename.as_deref().unwrap_or("input.txt"));
+    let frequencies = frequencies.as_deref();
 
-    // Part 2
-    let mut unique_locations = HashSet::new();
+    if let Some(harmonic) = harmonic {
+        let result = count_antinodes(&map, &harmonic, frequencies, report, render);
+        println!("{result}");
+        return;
+    }
 
-    for (index, first_antenna) in map.antennas.iter().enumerate() {
-        for second_antenna in map.antennas.iter().skip(index + 1) {
-            if first_antenna.frequency == second_antenna.frequency {
-                let antinodes = get_antinodes_2(&map, first_antenna, second_antenna);
-                for antinode in antinodes {
-                    unique_locations.insert(antinode);
-                }
-            }
-        }
+    // Part 1
+    let harmonic = Harmonic::Ratio { near: 1, far: 2 };
+    let result = count_antinodes(&map, &harmonic, frequencies, report, render);
+    println!("{result}");
+    if is_puzzle {
+        assert_eq!(result, 361);
     }
 
-    let result = unique_locations.len();
-    assert_eq!(result, 1249);
+    // Part 2
+    let result = count_antinodes(&map, &Harmonic::Collinear, frequencies, report, render);
     println!("{result}");
+    if is_puzzle {
+        assert_eq!(result, 1249);
+    }
 }
@@ -0,0 +1,132 @@
+// Advent of Code 2024 - Day 8
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{collections::BTreeMap, fmt};
+
+/// Marks a cell without an antenna.
+const EMPTY: char = '.';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Antenna {
+    pub frequency: char,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub width: i32,
+    pub height: i32,
+    pub antennas: Vec<Antenna>,
+}
+
+impl Map {
+    /// Creates a map from antennas, which have to lie on the map.
+    pub fn new(width: i32, height: i32, antennas: Vec<Antenna>) -> Map {
+        let map = Map {
+            width,
+            height,
+            antennas: Vec::new(),
+        };
+        for antenna in &antennas {
+            assert!(
+                map.contains(antenna.x, antenna.y),
+                "antenna {} at {},{} is outside the {width}x{height} map",
+                antenna.frequency,
+                antenna.x,
+                antenna.y
+            );
+        }
+
+        Map { antennas, ..map }
+    }
+
+    /// Parses a map where every character except `.` is an antenna of that frequency. Rows have
+    /// to be of equal width, lines may end with LF or CRLF.
+    pub fn parse(contents: &str) -> Result<Map, ParseError> {
+        let rows: Vec<&str> = contents.trim_end().lines().collect();
+        let Some(first) = rows.first() else {
+            return Err(ParseError {
+                line: 1,
+                message: "the map is empty".to_string(),
+            });
+        };
+        let width = first.chars().count();
+
+        let mut antennas = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let error = |message: String| ParseError {
+                line: y + 1,
+                message,
+            };
+
+            let row_width = row.chars().count();
+            if row_width != width {
+                return Err(error(format!(
+                    "expected {width} columns like the first row, found {row_width}"
+                )));
+            }
+
+            for (x, frequency) in row.chars().enumerate() {
+                if frequency == EMPTY {
+                    continue;
+                }
+                if frequency.is_whitespace() || frequency.is_control() {
+                    return Err(error(format!(
+                        "unexpected character {frequency:?} in column {}",
+                        x + 1
+                    )));
+                }
+
+                antennas.push(Antenna {
+                    frequency,
+                    x: x as i32,
+                    y: y as i32,
+                });
+            }
+        }
+
+        Ok(Map::new(width as i32, rows.len() as i32, antennas))
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        0 <= x && x < self.width && 0 <= y && y < self.height
+    }
+
+    /// Groups the antennas by frequency.
+    pub fn frequencies(&self) -> BTreeMap<char, Vec<&Antenna>> {
+        let mut frequencies: BTreeMap<char, Vec<&Antenna>> = BTreeMap::new();
+        for antenna in &self.antennas {
+            frequencies
+                .entry(antenna.frequency)
+                .or_default()
+                .push(antenna);
+        }
+
+        frequencies
+    }
+}
@@ -0,0 +1,119 @@
+// Advent of Code 2024 - Day 8
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{
+    antinodes::{self, Harmonic},
+    Map,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+#[derive(Debug, Clone)]
+pub struct FrequencyReport {
+    pub frequency: char,
+    pub antennas: usize,
+    pub antinodes: HashSet<(i32, i32)>,
+    /// Antinodes that are also antinodes of another frequency.
+    pub shared: usize,
+    /// Antinodes on an antenna of any frequency.
+    pub on_antennas: usize,
+}
+
+impl fmt::Display for FrequencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} antennas, {} antinodes, {} shared with other frequencies, {} on antennas",
+            self.frequency,
+            self.antennas,
+            self.antinodes.len(),
+            self.shared,
+            self.on_antennas
+        )
+    }
+}
+
+/// Returns a report for every frequency on the map, ordered by frequency.
+pub fn frequency_reports(map: &Map, harmonic: &Harmonic) -> Vec<FrequencyReport> {
+    let frequencies = map.frequencies();
+    let antinodes: Vec<(char, HashSet<(i32, i32)>)> = frequencies
+        .iter()
+        .map(|(frequency, antennas)| {
+            (
+                *frequency,
+                antinodes::frequency_antinodes(map, antennas, harmonic),
+            )
+        })
+        .collect();
+
+    // How many frequencies have an antinode at each location
+    let mut frequency_count: HashMap<(i32, i32), usize> = HashMap::new();
+    for (_, locations) in &antinodes {
+        for location in locations {
+            *frequency_count.entry(*location).or_default() += 1;
+        }
+    }
+
+    let antenna_locations: HashSet<(i32, i32)> = map
+        .antennas
+        .iter()
+        .map(|antenna| (antenna.x, antenna.y))
+        .collect();
+
+    antinodes
+        .into_iter()
+        .map(|(frequency, antinodes)| FrequencyReport {
+            frequency,
+            antennas: frequencies[&frequency].len(),
+            shared: antinodes
+                .iter()
+                .filter(|location| frequency_count[location] > 1)
+                .count(),
+            on_antennas: antinodes
+                .iter()
+                .filter(|location| antenna_locations.contains(location))
+                .count(),
+            antinodes,
+        })
+        .collect()
+}
+
+/// Draws the map with the antennas of the reported frequencies and a `#` for each of their
+/// antinodes, antennas are drawn on top of antinodes.
+pub fn render(map: &Map, reports: &[&FrequencyReport]) -> String {
+    let mut grid = vec![vec!['.'; map.width as usize]; map.height as usize];
+    for report in reports {
+        for (x, y) in &report.antinodes {
+            grid[*y as usize][*x as usize] = '#';
+        }
+    }
+    for antenna in &map.antennas {
+        if reports
+            .iter()
+            .any(|report| report.frequency == antenna.frequency)
+        {
+            grid[antenna.y as usize][antenna.x as usize] = antenna.frequency;
+        }
+    }
+
+    let mut output = String::new();
+    for row in grid {
+        output.extend(row);
+        output.push('\n');
+    }
+
+    output
+}
@@ -0,0 +1,517 @@
+// Advent of Code 2024 - Day 9
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{calculate_checksum, Segment};
+use std::{collections::BTreeSet, fmt, ops::Range};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Moves single blocks from the end of the disk into the leftmost free block.
+    PartOne,
+    /// Moves whole files, highest identifier first, into the leftmost free span they fit in.
+    PartTwo,
+    /// Like `PartTwo`, but into the smallest free span the file fits in.
+    BestFit,
+    /// Like `PartTwo`, but into the largest free span.
+    WorstFit,
+    /// Like `PartTwo`, but searches from the end of the previous move and wraps around.
+    NextFit,
+    /// Moves every file directly behind the previous one, leaving no free blocks between files.
+    Defragment,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 6] = [
+        Algorithm::PartOne,
+        Algorithm::PartTwo,
+        Algorithm::BestFit,
+        Algorithm::WorstFit,
+        Algorithm::NextFit,
+        Algorithm::Defragment,
+    ];
+
+    /// Looks up an algorithm by the name it is displayed with.
+    pub fn parse(name: &str) -> Option<Algorithm> {
+        Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.to_string() == name)
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Algorithm::PartOne => "blocks",
+            Algorithm::PartTwo => "first-fit",
+            Algorithm::BestFit => "best-fit",
+            Algorithm::WorstFit => "worst-fit",
+            Algorithm::NextFit => "next-fit",
+            Algorithm::Defragment => "defragment",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Moves `length` blocks of a file from `from` to `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorderingInstruction {
+    pub identifier: usize,
+    pub from: u64,
+    pub to: u64,
+    pub length: u64,
+}
+
+impl ReorderingInstruction {
+    /// Applies the move to the disk, one entry per block.
+    pub fn apply(&self, blocks: &mut [Option<usize>]) {
+        let (from, to, length) = (self.from as usize, self.to as usize, self.length as usize);
+        blocks[from..from + length].fill(None);
+        blocks[to..to + length].fill(Some(self.identifier));
+    }
+}
+
+impl fmt::Display for ReorderingInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} blocks of file {} from {} to {}",
+            self.length, self.identifier, self.from, self.to
+        )
+    }
+}
+
+/// A file, or the part of a file, stored in consecutive blocks.
+#[derive(Debug, Clone)]
+struct Extent {
+    identifier: usize,
+    position: u64,
+    length: u64,
+}
+
+/// The free spans of the disk. Spans are only ever used from their start, so each stays in the
+/// slot it started in, and slots remain ordered by position.
+///
+/// A segment tree over the slots, holding the longest span of every subtree, finds the leftmost
+/// span of a length, and a set ordered by length finds the shortest or longest one, each in
+/// O(log n).
+struct FreeSpans {
+    /// Position and remaining length of every span.
+    spans: Vec<(u64, u64)>,
+    /// Number of leaves of the segment tree, a power of two.
+    leaves: usize,
+    /// Longest span below each node, the root is node 1 and the children of `n` are `2n, 2n + 1`.
+    longest: Vec<u64>,
+    /// Length, position and slot of every span that is not used up.
+    by_length: BTreeSet<(u64, u64, usize)>,
+}
+
+impl FreeSpans {
+    /// Creates the free spans from their positions and lengths, ordered by position.
+    fn new(spans: Vec<(u64, u64)>) -> FreeSpans {
+        let leaves = spans.len().next_power_of_two();
+        let mut longest = vec![0; 2 * leaves];
+        for (slot, (_, length)) in spans.iter().enumerate() {
+            longest[leaves + slot] = *length;
+        }
+        for node in (1..leaves).rev() {
+            longest[node] = longest[2 * node].max(longest[2 * node + 1]);
+        }
+
+        let by_length = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, length))| *length > 0)
+            .map(|(slot, (position, length))| (*length, *position, slot))
+            .collect();
+
+        FreeSpans {
+            spans,
+            leaves,
+            longest,
+            by_length,
+        }
+    }
+
+    /// Uses the first `length` blocks of the span in the slot, returns their position.
+    fn allocate(&mut self, slot: usize, length: u64) -> u64 {
+        let (position, span_length) = self.spans[slot];
+        self.by_length.remove(&(span_length, position, slot));
+        self.spans[slot] = (position + length, span_length - length);
+        if span_length > length {
+            self.by_length
+                .insert((span_length - length, position + length, slot));
+        }
+
+        let mut node = self.leaves + slot;
+        self.longest[node] = span_length - length;
+        while node > 1 {
+            node /= 2;
+            self.longest[node] = self.longest[2 * node].max(self.longest[2 * node + 1]);
+        }
+
+        position
+    }
+
+    /// Returns the leftmost slot from `first` on with a span of at least `length` blocks.
+    fn find(&self, node: usize, nodes: Range<usize>, first: usize, length: u64) -> Option<usize> {
+        if nodes.end <= first || self.longest[node] < length {
+            return None;
+        }
+        if nodes.len() == 1 {
+            return Some(nodes.start);
+        }
+
+        let middle = nodes.start + nodes.len() / 2;
+        self.find(2 * node, nodes.start..middle, first, length)
+            .or_else(|| self.find(2 * node + 1, middle..nodes.end, first, length))
+    }
+
+    /// Returns the slot of the leftmost span of at least `length` blocks that starts within
+    /// `positions`.
+    fn leftmost(&self, length: u64, positions: Range<u64>) -> Option<usize> {
+        let first = self
+            .spans
+            .partition_point(|(position, _)| *position < positions.start);
+        let slot = self.find(1, 0..self.leaves, first, length.max(1))?;
+        (self.spans[slot].0 < positions.end).then_some(slot)
+    }
+
+    /// Returns the slot of the leftmost of the shortest, or longest, spans of at least `length`
+    /// blocks that start before `limit`. The limit may never grow between calls, as spans beyond
+    /// it are dropped.
+    fn by_length(&mut self, length: u64, limit: u64, longest: bool) -> Option<usize> {
+        loop {
+            let (span_length, position, slot) = if longest {
+                let (longest, _, _) = *self.by_length.last()?;
+                *self.by_length.range((longest, 0, 0)..).next()?
+            } else {
+                *self.by_length.range((length.max(1), 0, 0)..).next()?
+            };
+            if span_length < length {
+                return None;
+            }
+            if position < limit {
+                return Some(slot);
+            }
+
+            // Out of reach for this file and all files still to move
+            self.by_length.remove(&(span_length, position, slot));
+        }
+    }
+}
+
+/// The disk after compaction.
+#[derive(Debug, Clone)]
+pub struct Compaction {
+    /// Files and free spans covering the whole disk, adjacent free spans are merged. Files without
+    /// blocks stay where they were.
+    pub layout: Vec<Segment>,
+    /// The moves, in the order they were made.
+    pub instructions: Vec<ReorderingInstruction>,
+}
+
+/// Compacts the disk with the algorithm.
+pub fn compact(segments: &[Segment], algorithm: Algorithm) -> Compaction {
+    let mut files = Vec::new();
+    let mut placed = Vec::new();
+    let mut free_spans = Vec::new();
+    let mut position = 0;
+    for segment in segments {
+        match segment.identifier {
+            Some(identifier) => {
+                let file = Extent {
+                    identifier,
+                    position,
+                    length: segment.length,
+                };
+                if segment.length > 0 {
+                    files.push(file);
+                } else {
+                    // Nothing to move
+                    placed.push(file);
+                }
+            }
+            None => free_spans.push((position, segment.length)),
+        }
+        position += segment.length;
+    }
+    let size = position;
+    let mut free = FreeSpans::new(free_spans);
+
+    let mut instructions = Vec::new();
+    match algorithm {
+        Algorithm::PartOne => {
+            while let Some(mut file) = files.pop() {
+                while let Some(slot) = free.leftmost(1, 0..file.position) {
+                    // Take the blocks from the end of the file
+                    let moved = free.spans[slot].1.min(file.length);
+                    let position = free.allocate(slot, moved);
+                    file.length -= moved;
+                    instructions.push(ReorderingInstruction {
+                        identifier: file.identifier,
+                        from: file.position + file.length,
+                        to: position,
+                        length: moved,
+                    });
+                    placed.push(Extent {
+                        identifier: file.identifier,
+                        position,
+                        length: moved,
+                    });
+
+                    if file.length == 0 {
+                        break;
+                    }
+                }
+
+                if file.length > 0 {
+                    // No free block left of this file, so none for the files before it either
+                    placed.push(file);
+                    placed.append(&mut files);
+                }
+            }
+        }
+        Algorithm::PartTwo | Algorithm::BestFit | Algorithm::WorstFit | Algorithm::NextFit => {
+            // Where next-fit continues searching
+            let mut cursor = 0;
+
+            while let Some(mut file) = files.pop() {
+                let span = match algorithm {
+                    Algorithm::PartTwo => free.leftmost(file.length, 0..file.position),
+                    Algorithm::BestFit => free.by_length(file.length, file.position, false),
+                    Algorithm::WorstFit => free.by_length(file.length, file.position, true),
+                    Algorithm::NextFit => {
+                        let cursor = cursor.min(file.position);
+                        free.leftmost(file.length, cursor..file.position)
+                            .or_else(|| free.leftmost(file.length, 0..cursor))
+                    }
+                    Algorithm::PartOne | Algorithm::Defragment => unreachable!(),
+                };
+
+                if let Some(slot) = span {
+                    let position = free.allocate(slot, file.length);
+                    instructions.push(ReorderingInstruction {
+                        identifier: file.identifier,
+                        from: file.position,
+                        to: position,
+                        length: file.length,
+                    });
+                    file.position = position;
+                    cursor = position + file.length;
+                }
+                placed.push(file);
+            }
+        }
+        Algorithm::Defragment => {
+            let mut position = 0;
+            for mut file in files {
+                if file.position != position {
+                    instructions.push(ReorderingInstruction {
+                        identifier: file.identifier,
+                        from: file.position,
+                        to: position,
+                        length: file.length,
+                    });
+                    file.position = position;
+                }
+                position += file.length;
+                placed.push(file);
+            }
+        }
+    }
+
+    // Files without blocks go before a file moved to the same position, as they were there first
+    placed.sort_by_key(|extent| (extent.position, extent.length > 0));
+    let mut layout = Vec::new();
+    let mut position = 0;
+    for extent in placed {
+        if extent.position > position {
+            layout.push(Segment::new_empty(extent.position - position));
+        }
+        layout.push(Segment::new_file(extent.length, extent.identifier));
+        position = extent.position + extent.length;
+    }
+    if size > position {
+        layout.push(Segment::new_empty(size - position));
+    }
+
+    Compaction {
+        layout,
+        instructions,
+    }
+}
+
+/// Compares the result of compaction algorithms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub checksum: u128,
+    /// Number of free spans on the disk, including the one at the end.
+    pub free_runs: usize,
+    pub largest_free_run: u64,
+    pub moves: usize,
+}
+
+impl Report {
+    pub fn new(compaction: &Compaction) -> Report {
+        // A file without blocks does not split the free span around it
+        let mut free_runs: Vec<u64> = Vec::new();
+        let mut in_free_run = false;
+        for segment in compaction
+            .layout
+            .iter()
+            .filter(|segment| segment.length > 0)
+        {
+            match (segment.identifier, in_free_run) {
+                (None, true) => *free_runs.last_mut().unwrap() += segment.length,
+                (None, false) => free_runs.push(segment.length),
+                (Some(_), _) => (),
+            }
+            in_free_run = segment.identifier.is_none();
+        }
+
+        Report {
+            checksum: calculate_checksum(&compaction.layout),
+            free_runs: free_runs.len(),
+            largest_free_run: free_runs.into_iter().max().unwrap_or(0),
+            moves: compaction.instructions.len(),
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum {}, {} free runs, largest free run {}, {} moves",
+            self.checksum, self.free_runs, self.largest_free_run, self.moves
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk_map;
+
+    /// Moves whole files like `compact`, but finds the free spans by scanning all of them.
+    fn reference_moves(segments: &[Segment], algorithm: Algorithm) -> Vec<ReorderingInstruction> {
+        let mut files = Vec::new();
+        let mut spans = Vec::new();
+        let mut position = 0;
+        for segment in segments {
+            match segment.identifier {
+                Some(identifier) if segment.length > 0 => {
+                    files.push((identifier, position, segment.length))
+                }
+                Some(_) => (),
+                None => spans.push((position, segment.length)),
+            }
+            position += segment.length;
+        }
+
+        let mut instructions = Vec::new();
+        let mut cursor = 0;
+        for (identifier, from, length) in files.into_iter().rev() {
+            let candidates = spans
+                .iter()
+                .enumerate()
+                .filter(|(_, (position, span_length))| *span_length >= length && *position < from);
+            let slot = match algorithm {
+                Algorithm::PartTwo => candidates.min_by_key(|(_, (position, _))| *position),
+                Algorithm::BestFit => {
+                    candidates.min_by_key(|(_, (position, span))| (*span, *position))
+                }
+                Algorithm::WorstFit => candidates
+                    .min_by_key(|(_, (position, span))| (std::cmp::Reverse(*span), *position)),
+                Algorithm::NextFit => {
+                    let cursor = cursor.min(from);
+                    candidates.min_by_key(|(_, (position, _))| (*position < cursor, *position))
+                }
+                Algorithm::PartOne | Algorithm::Defragment => unreachable!(),
+            }
+            .map(|(slot, _)| slot);
+
+            if let Some(slot) = slot {
+                let (to, span_length) = spans[slot];
+                spans[slot] = (to + length, span_length - length);
+                cursor = to + length;
+                instructions.push(ReorderingInstruction {
+                    identifier,
+                    from,
+                    to,
+                    length,
+                });
+            }
+        }
+
+        instructions
+    }
+
+    #[test]
+    fn free_spans_match_a_linear_scan() {
+        // Lengths up to 60 blocks, so that there are many distinct free span lengths
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let lengths: Vec<String> = (0..2000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % 61).to_string()
+            })
+            .collect();
+        let segments = disk_map::parse(&lengths.join(",")).unwrap();
+
+        for algorithm in [
+            Algorithm::PartTwo,
+            Algorithm::BestFit,
+            Algorithm::WorstFit,
+            Algorithm::NextFit,
+        ] {
+            assert_eq!(
+                compact(&segments, algorithm).instructions,
+                reference_moves(&segments, algorithm),
+                "{algorithm}"
+            );
+        }
+    }
+
+    #[test]
+    fn example_checksums() {
+        let segments = disk_map::parse("2333133121414131402").unwrap();
+        assert_eq!(
+            calculate_checksum(&compact(&segments, Algorithm::PartOne).layout),
+            1928
+        );
+        assert_eq!(
+            calculate_checksum(&compact(&segments, Algorithm::PartTwo).layout),
+            2858
+        );
+    }
+
+    #[test]
+    fn checksum_of_long_files() {
+        // 12 files of 3,000,000,000 blocks overflow a `u64` checksum
+        let length: u128 = 3_000_000_000;
+        let segments = disk_map::parse(&["3000000000"; 12].join(",")).unwrap();
+        let expected: u128 = (0..12u128)
+            .filter(|index| index % 2 == 0)
+            .map(|index| {
+                let position = index * length;
+                index / 2 * (length * position + length * (length - 1) / 2)
+            })
+            .sum();
+        assert!(expected > u128::from(u64::MAX));
+        assert_eq!(calculate_checksum(&segments), expected);
+    }
+}
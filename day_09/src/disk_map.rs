@@ -0,0 +1,191 @@
+// Advent of Code 2024 - Day 9
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::Segment;
+
+/// Separates the lengths in the extended format, e.g. `2,3,3,3,13,3`.
+const SEPARATOR: char = ',';
+
+/// One character per identifier, larger identifiers wrap around so that every block stays one
+/// character wide. Files whose identifiers differ by a multiple of 62 share a character, and once
+/// files have moved they can end up next to each other.
+const SYMBOLS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Parses the lengths of alternating files and free spans. Either one digit per length, as in
+/// the puzzle, or lengths of any size separated by commas, which is detected automatically.
+pub fn parse(contents: &str) -> Result<Vec<Segment>, String> {
+    let contents = contents.trim();
+    let lengths: Vec<u64> = if contents.contains(SEPARATOR) {
+        contents
+            .split(SEPARATOR)
+            .map(|length| {
+                let length = length.trim();
+                length
+                    .parse()
+                    .map_err(|e| format!("invalid length {length:?}: {e}"))
+            })
+            .collect::<Result<_, _>>()?
+    } else {
+        contents
+            .chars()
+            .map(|c| {
+                c.to_digit(10)
+                    .map(u64::from)
+                    .ok_or_else(|| format!("invalid length {c:?}"))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    Ok(lengths
+        .into_iter()
+        .enumerate()
+        .map(|(index, length)| {
+            if index % 2 == 0 {
+                Segment::new_file(length, index / 2)
+            } else {
+                Segment::new_empty(length)
+            }
+        })
+        .collect())
+}
+
+/// Returns the identifier of the file in each block, `None` for free blocks.
+pub fn blocks(segments: &[Segment]) -> Vec<Option<usize>> {
+    segments
+        .iter()
+        .flat_map(|segment| std::iter::repeat_n(segment.identifier, segment.length as usize))
+        .collect()
+}
+
+/// Draws one character per block, e.g. `00...111...2...`, identifiers above 9 continue with
+/// `a`-`z` and `A`-`Z`. Identifiers above 61 start over at `0`, so the drawing only tells files
+/// apart on disks with fewer than 62 files.
+pub fn render(blocks: &[Option<usize>]) -> String {
+    blocks
+        .iter()
+        .map(|block| {
+            block.map_or('.', |identifier| {
+                SYMBOLS[identifier % SYMBOLS.len()] as char
+            })
+        })
+        .collect()
+}
+
+/// Writes the layout in the dense format of the puzzle input, or in the comma separated format if
+/// a length does not fit into a single digit. This is only possible if the files are in order of
+/// their identifiers and each stored in one piece, files that are missing are written with a
+/// length of 0.
+pub fn serialize(segments: &[Segment]) -> Result<String, String> {
+    // Merge neighbouring segments of the same file or of free blocks, files without blocks are
+    // kept as they separate free spans
+    let mut merged: Vec<Segment> = Vec::new();
+    for segment in segments {
+        if segment.identifier.is_none() && segment.length == 0 {
+            continue;
+        }
+        match merged.last_mut() {
+            Some(last) if last.identifier == segment.identifier => last.length += segment.length,
+            _ => merged.push(segment.clone()),
+        }
+    }
+
+    // Alternating file and free lengths, files are numbered by their position in here
+    let mut lengths = Vec::new();
+    let push_file = |lengths: &mut Vec<u64>, length: u64| {
+        if lengths.len() % 2 == 1 {
+            // Two files next to each other
+            lengths.push(0);
+        }
+        lengths.push(length);
+    };
+
+    for segment in merged {
+        match segment.identifier {
+            Some(identifier) => {
+                let next_identifier = lengths.len().div_ceil(2);
+                if identifier < next_identifier {
+                    return Err(format!(
+                        "file {identifier} is stored out of order or in several pieces"
+                    ));
+                }
+                for _ in next_identifier..identifier {
+                    push_file(&mut lengths, 0);
+                }
+                push_file(&mut lengths, segment.length);
+            }
+            None => {
+                if lengths.is_empty() {
+                    return Err("the disk starts with free blocks".to_string());
+                }
+                lengths.push(segment.length);
+            }
+        }
+    }
+
+    let is_dense = lengths.iter().all(|length| *length < 10);
+    let lengths: Vec<String> = lengths.iter().map(u64::to_string).collect();
+    if is_dense {
+        Ok(lengths.concat())
+    } else {
+        Ok(lengths.join(&SEPARATOR.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compactor::{self, Algorithm};
+
+    fn round_trip(disk_map: &str, algorithm: Algorithm) -> Result<String, String> {
+        let segments = parse(disk_map)?;
+        serialize(&compactor::compact(&segments, algorithm).layout)
+    }
+
+    #[test]
+    fn round_trip_keeps_files_without_blocks() {
+        assert_eq!(
+            round_trip("21013", Algorithm::PartTwo),
+            Ok("21013".to_string())
+        );
+        assert_eq!(
+            round_trip("2,1,0,1,3", Algorithm::PartTwo),
+            Ok("21013".to_string())
+        );
+        assert_eq!(round_trip("120", Algorithm::PartTwo), Ok("120".to_string()));
+        assert_eq!(round_trip("023", Algorithm::PartTwo), Ok("023".to_string()));
+    }
+
+    #[test]
+    fn round_trip_after_moves() {
+        assert_eq!(
+            round_trip("10021", Algorithm::PartTwo),
+            Ok("100012".to_string())
+        );
+        assert_eq!(
+            round_trip("2333133121414131402", Algorithm::Defragment),
+            Ok("2,0,3,0,1,0,3,0,2,0,4,0,4,0,3,0,4,0,2,14".to_string())
+        );
+    }
+
+    #[test]
+    fn render_wraps_identifiers() {
+        let blocks = [Some(0), Some(61), None, Some(62), Some(10)];
+        assert_eq!(render(&blocks), "0Z.0a");
+    }
+
+    #[test]
+    fn files_out_of_order_are_rejected() {
+        assert!(round_trip("2333133121414131402", Algorithm::PartTwo).is_err());
+    }
+}
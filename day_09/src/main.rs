@@ -12,252 +12,147 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::{borrow::BorrowMut, fs};
- 
+mod compactor;
+mod disk_map;
+
+use compactor::Algorithm;
+use std::{env, fs};
+
 #[derive(Debug, Clone)]
 struct Segment {
-    length: usize,
+    length: u64,
     identifier: Option<usize>,
 }
- 
+
 impl Segment {
-    fn new_file(length: usize, identifier: usize) -> Segment {
+    fn new_file(length: u64, identifier: usize) -> Segment {
         Segment {
             length,
             identifier: Some(identifier),
         }
     }
- 
-    fn new_empty(length: usize) -> Segment {
+
+    fn new_empty(length: u64) -> Segment {
         Segment {
             length,
             identifier: None,
         }
     }
- 
-    fn is_empty(&self) -> bool {
-        self.identifier.is_none()
-    }
 }
- 
+
 fn read_puzzle_input(filename: &str) -> Vec<Segment> {
     let contents = fs::read_to_string(filename).unwrap();
- 
-    contents
-        .trim()
-        .char_indices()
-        .zip([true, false].iter().cycle())
-        .map(|((index, c), is_file)| {
-            if *is_file {
-                Segment::new_file(c.to_digit(10).unwrap() as usize, index / 2)
-            } else {
-                Segment::new_empty(c.to_digit(10).unwrap() as usize)
-            }
-        })
-        .collect()
-}
- 
-#[derive(Debug)]
-struct ReorderingInstruction {
-    empty_block_index: usize,
-    file_index: usize,
-    new_empty_block_length: usize,
-    old_file_length: usize,
-    new_segment: Segment,
-}
- 
-enum Algorithm {
-    PartOne,
-    PartTwo { identifier: usize },
+
+    disk_map::parse(&contents).unwrap_or_else(|e| panic!("{filename}: {e}"))
 }
- 
-enum InstructionError {
-    NoReallocationPossible,
-    EndOfFile,
+
+/// Sums the position of every block times the identifier of its file. The sum can exceed `u64` on
+/// disks with long files, so it is kept in `u128`.
+fn calculate_checksum(segments: &[Segment]) -> u128 {
+    let mut result = 0;
+    let mut position = 0;
+    for segment in segments {
+        let length = u128::from(segment.length);
+        // Sum of the positions of all blocks in the segment
+        let positions = length * position + length * length.saturating_sub(1) / 2;
+        result += positions * segment.identifier.unwrap_or(0) as u128;
+        position += length;
+    }
+    result
 }
- 
-/// In hindsight the todays parts should probably have been kept apart. But now the refactoring is already done
-fn get_instruction(
-    segments: &[Segment],
-    algorithm: &Algorithm,
-) -> Result<ReorderingInstruction, InstructionError> {
-    let (first_empty_index, first_empty_segment, last_file_index, last_file) = match algorithm {
-        Algorithm::PartOne => {
-            let (first_empty_index, first_empty_segment) = match segments
-                .iter()
-                .enumerate()
-                .find(|(_, segment)| segment.is_empty() && segment.length > 0)
-            {
-                Some(a) => a,
-                None => return Err(InstructionError::EndOfFile),
-            };
- 
-            let (last_file_index, last_file) = match segments
-                .iter()
-                .enumerate()
-                .rev()
-                .find(|(_, segment)| !segment.is_empty() && segment.length > 0)
-            {
-                Some(a) => a,
-                None => return Err(InstructionError::EndOfFile),
-            };
- 
-            if first_empty_index > last_file_index {
-                return Err(InstructionError::EndOfFile);
-            }
- 
-            (
-                first_empty_index,
-                first_empty_segment,
-                last_file_index,
-                last_file,
-            )
-        }
-        Algorithm::PartTwo { identifier } => {
-            let (last_file_index, last_file) = match segments
-                .iter()
-                .enumerate()
-                .rev()
-                .find(|(_, segment)| segment.identifier == Some(*identifier))
-            {
-                Some(a) => a,
-                None => panic!(),
-            };
- 
-            let (first_empty_index, first_empty_segment) = match segments
-                .iter()
-                .enumerate()
-                .find(|(_, segment)| segment.is_empty() && segment.length >= last_file.length)
-            {
-                Some(a) => a,
-                None => return Err(InstructionError::EndOfFile),
-            };
- 
-            if first_empty_index > last_file_index {
-                return Err(InstructionError::NoReallocationPossible);
-            }
- 
-            (
-                first_empty_index,
-                first_empty_segment,
-                last_file_index,
-                last_file,
-            )
-        }
-    };
- 
-    let new_segment_length = std::cmp::min(first_empty_segment.length, last_file.length);
-    let empty_length = first_empty_segment.length - new_segment_length;
-    let old_file_length = last_file.length - new_segment_length;
- 
-    Ok(ReorderingInstruction {
-        empty_block_index: first_empty_index,
-        file_index: last_file_index,
-        new_empty_block_length: empty_length,
-        old_file_length,
-        new_segment: Segment {
-            length: new_segment_length,
-            identifier: last_file.identifier,
-        },
-    })
+
+/// Prints the moves of a compaction one by one, each followed by the disk it leaves behind.
+fn print_trace(segments: &[Segment], instructions: &[compactor::ReorderingInstruction]) {
+    let mut blocks = disk_map::blocks(segments);
+    println!("{}", disk_map::render(&blocks));
+    for instruction in instructions {
+        instruction.apply(&mut blocks);
+        println!("{instruction}");
+        println!("{}", disk_map::render(&blocks));
+    }
 }
- 
-fn run_compactor(segments: &mut Vec<Segment>, algorithm: Algorithm) {
-    let mut algorithm = algorithm;
- 
-    loop {
-        // Remove empty blocks
-        segments.retain(|segment| segment.length > 0);
- 
-        // Merge empty blocks
-        let mut new_segments: Vec<Segment> = Vec::new();
-        for segment in segments.iter_mut() {
-            match (new_segments.last(), segment) {
-                (Some(last_segment), segment) if last_segment.is_empty() && segment.is_empty() => {
-                    new_segments.last_mut().unwrap().length += segment.length;
-                }
-                (_, segment) => new_segments.push(segment.clone()),
-            }
-        }
-        *segments = new_segments;
- 
-        match get_instruction(segments, &algorithm) {
-            Ok(instruction) => {
-                segments
-                    .get_mut(instruction.empty_block_index)
-                    .unwrap()
-                    .length = instruction.new_empty_block_length;
- 
-                segments.get_mut(instruction.file_index).unwrap().length =
-                    instruction.old_file_length;
-                segments.insert(
-                    instruction.file_index,
-                    Segment::new_empty(instruction.new_segment.length),
+
+/// Usage: `day_09 [--compare] [--algorithm <name>] [--render] [--trace] [--serialize] [FILE]`
+///
+/// `--compare` compacts the disk with every algorithm and prints their checksums, fragmentation
+/// and number of moves. `--algorithm` compacts with one of `blocks`, `first-fit`, `best-fit`,
+/// `worst-fit`, `next-fit` or `defragment` instead of parts 1 and 2. `--render` draws the disk
+/// after compaction, one character per block with identifiers wrapping after 61, `--trace` draws
+/// it after every move and `--serialize` writes it in the format of the puzzle input.
+fn main() {
+    let mut compare = false;
+    let mut algorithm = None;
+    let mut render = false;
+    let mut trace = false;
+    let mut serialize = false;
+    let mut filename = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compare" => compare = true,
+            "--algorithm" => {
+                let name = args.next().expect("--algorithm requires a name");
+                algorithm = Some(
+                    Algorithm::parse(&name).unwrap_or_else(|| panic!("unknown algorithm {name}")),
                 );
- 
-                segments.insert(instruction.empty_block_index, instruction.new_segment);
             }
-            Err(InstructionError::EndOfFile) => break,
-            Err(InstructionError::NoReallocationPossible) => (),
+            "--render" => render = true,
+            "--trace" => trace = true,
+            "--serialize" => serialize = true,
+            _ => filename = Some(arg),
         }
- 
-        if let Algorithm::PartTwo { identifier } = algorithm.borrow_mut() {
-            if *identifier == 0 {
-                break;
-            }
- 
-            *identifier -= 1;
+    }
+
+    let is_puzzle = filename.is_none();
+    let segments = read_puzzle_input(filename.as_deref().unwrap_or("input.txt"));
+
+    if compare {
+        for algorithm in Algorithm::ALL {
+            let report = compactor::Report::new(&compactor::compact(&segments, algorithm));
+            println!("{algorithm}: {report}");
         }
+        return;
     }
-}
- 
-fn calculate_checksum(segments: &[Segment]) -> usize {
-    let mut result = 0;
-    let mut index_counter = 0;
-    for segment in segments {
-        for _ in 0..segment.length {
-            result += index_counter * segment.identifier.unwrap_or(0);
-            index_counter += 1;
+
+    let run = |algorithm: Algorithm| {
+        let compaction = compactor::compact(&segments, algorithm);
+        if trace {
+            print_trace(&segments, &compaction.instructions);
+        } else if render {
+            println!(
+                "{}",
+                disk_map::render(&disk_map::blocks(&compaction.layout))
+            );
+        }
+        if serialize {
+            match disk_map::serialize(&compaction.layout) {
+                Ok(disk_map) => println!("{disk_map}"),
+                Err(e) => println!("can not serialize: {e}"),
+            }
         }
+
+        calculate_checksum(&compaction.layout)
+    };
+
+    if let Some(algorithm) = algorithm {
+        println!("{}", run(algorithm));
+        return;
     }
-    result
-}
 
-fn main() {
-    let segments = read_puzzle_input("input.txt");
- 
-    let mut segments_1 = segments.clone();
-    let mut segments_2 = segments;
- 
     // Part 1
-    // This might take some time to run in debug mode, run with `--release` for faster execution
- 
-    run_compactor(&mut segments_1, Algorithm::PartOne);
- 
-    let result = calculate_checksum(&segments_1);
- 
+    let result = run(Algorithm::PartOne);
+
     println!("{result}");
-    assert_eq!(result, 6398608069280);
- 
+    if is_puzzle {
+        assert_eq!(result, 6398608069280);
+    }
+
     // Part 2
- 
-    let last_identifier = segments_2
-        .iter()
-        .rev()
-        .find(|segment| !segment.is_empty() && segment.length > 0)
-        .unwrap()
-        .identifier
-        .unwrap();
- 
-    run_compactor(
-        &mut segments_2,
-        Algorithm::PartTwo {
-            identifier: last_identifier,
-        },
-    );
- 
-    let result = calculate_checksum(&segments_2);
- 
+    let result = run(Algorithm::PartTwo);
+
     println!("{result}");
-    assert_eq!(result, 6427437134372);
-}
\ No newline at end of file
+    if is_puzzle {
+        assert_eq!(result, 6427437134372);
+    }
+}
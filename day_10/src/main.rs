@@ -12,7 +12,10 @@
 ///
 /// You should have received a copy of the GNU General Public License
 /// along with this program. If not, see <https://www.gnu.org/licenses/>.
-use std::{collections::HashSet, fs};
+mod trails;
+
+use std::{env, fs};
+use trails::{Neighbourhood, Rules, TrailTable};
 
 fn read_puzzle_input(filename: &str) -> Vec<Vec<i32>> {
     let contents = fs::read_to_string(filename).unwrap();
@@ -28,106 +31,78 @@ fn read_puzzle_input(filename: &str) -> Vec<Vec<i32>> {
         .collect()
 }
 
-fn find_edges(map: &[Vec<i32>], start_position: (usize, usize)) -> Vec<(usize, usize)> {
-    let width = map.first().unwrap().len() as i32;
-    let height = map.len() as i32;
-
-    let mut result = Vec::new();
-
-    let mut visited_nodes = HashSet::new();
-    let mut to_investigate = vec![start_position];
-
-    while let Some((x, y)) = to_investigate.pop() {
-        visited_nodes.insert((x, y));
-
-        let value = map.get(y).unwrap().get(x).unwrap();
-        if *value == 9 {
-            result.push((x, y));
-            continue;
-        }
+/// Parses two numbers separated by a comma, e.g. `-1,1`.
+fn parse_pair(value: &str) -> (i32, i32) {
+    value
+        .split_once(',')
+        .and_then(|(a, b)| Some((a.parse().ok()?, b.parse().ok()?)))
+        .unwrap_or_else(|| panic!("expected two numbers separated by a comma, found {value}"))
+}
 
-        let x = x as i32;
-        let y = y as i32;
-        for (px, py) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
-            if px < 0 || py < 0 || px >= width || py >= height {
-                continue;
+/// Usage: `day_10 [--heights <start,end>] [--steps <min,max>] [--diagonal] [--trails <n>] [FILE]`
+///
+/// `--heights` and `--steps` change where trails start and end and by how much the height may
+/// change per step, `--diagonal` also allows diagonal steps. `--trails` lists up to `n` trails
+/// for every trailhead.
+fn main() {
+    let mut rules = Rules::default();
+    let mut trail_limit = None;
+    let mut filename = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--heights" => {
+                let (start, end) = parse_pair(&args.next().expect("--heights requires start,end"));
+                rules = rules.with_heights(start, end);
             }
-
-            if visited_nodes.contains(&(px as usize, py as usize)) {
-                continue;
+            "--steps" => {
+                let (min_step, max_step) =
+                    parse_pair(&args.next().expect("--steps requires min,max"));
+                rules = rules.with_steps(min_step, max_step);
             }
-
-            if *map.get(py as usize).unwrap().get(px as usize).unwrap() == value + 1 {
-                to_investigate.push((px as usize, py as usize));
+            "--diagonal" => rules = rules.with_neighbourhood(Neighbourhood::Eight),
+            "--trails" => {
+                let limit = args.next().expect("--trails requires a number");
+                trail_limit = Some(limit.parse().expect("invalid number of trails"));
             }
+            _ => filename = Some(arg),
         }
     }
 
-    result
-}
+    let is_puzzle = filename.is_none() && rules == Rules::default();
+    let map = read_puzzle_input(filename.as_deref().unwrap_or("input.txt"));
 
-fn find_edges_2(map: &[Vec<i32>], start_position: (usize, usize)) -> Vec<(usize, usize)> {
-    let width = map.first().unwrap().len() as i32;
-    let height = map.len() as i32;
+    let table = TrailTable::new(&map, &rules);
+    let trailheads = table.trailheads();
 
-    let mut result = Vec::new();
-
-    let mut to_investigate = vec![start_position];
-
-    while let Some((x, y)) = to_investigate.pop() {
-        let value = map.get(y).unwrap().get(x).unwrap();
-        if *value == 9 {
-            result.push((x, y));
-            continue;
-        }
-
-        let x = x as i32;
-        let y = y as i32;
-        for (px, py) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
-            if px < 0 || py < 0 || px >= width || py >= height {
-                continue;
-            }
-
-            if *map.get(py as usize).unwrap().get(px as usize).unwrap() == value + 1 {
-                to_investigate.push((px as usize, py as usize));
+    if let Some(limit) = trail_limit {
+        for trailhead in &trailheads {
+            for trail in table.list_trails(*trailhead, limit) {
+                let cells: Vec<String> = trail.iter().map(|(x, y)| format!("{x},{y}")).collect();
+                println!("{}", cells.join(" -> "));
             }
         }
     }
 
-    result
-}
-
-fn main() {
-    let map = read_puzzle_input("input.txt");
-
-    let starting_points: Vec<(usize, usize)> = map
-        .iter()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(move |(x, value)| match value {
-                    0 => Some((x, y)),
-                    _ => None,
-                })
-        })
-        .collect();
+    // Part 1
+    let result: usize = trailheads.iter().map(|pos| table.score(*pos)).sum();
 
-    let mut result = 0;
-    for pos in &starting_points {
-        let edges = find_edges(&map, *pos);
-        result += edges.len();
+    println!("{result}");
+    if is_puzzle {
+        assert_eq!(result, 709);
     }
 
-    println!("{result}");
-    assert_eq!(result, 709);
+    // Part 2
+    let result: Option<u64> = trailheads.iter().map(|pos| table.rating(*pos)).sum();
 
-    let mut result = 0;
-    for pos in starting_points {
-        let edges = find_edges_2(&map, pos);
-        result += edges.len();
+    match result {
+        Some(result) => {
+            println!("{result}");
+            if is_puzzle {
+                assert_eq!(result, 1326);
+            }
+        }
+        None => println!("trails can not be counted when steps may go up and down"),
     }
-
-    println!("{result}");
-    assert_eq!(result, 1326);
 }
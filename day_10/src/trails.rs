@@ -0,0 +1,324 @@
+// Advent of Code 2024 - Day 10
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Up, down, left and right.
+    Four,
+    /// Also diagonally.
+    Eight,
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Neighbourhood::Eight => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (-1, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// What makes a trail, by default those of the puzzle: from height 0 to 9, climbing exactly one
+/// per step, up, down, left or right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Height of a trailhead.
+    pub start: i32,
+    /// Height of a summit, trails end at the first summit they reach.
+    pub end: i32,
+    /// Smallest height difference of a step.
+    pub min_step: i32,
+    /// Largest height difference of a step.
+    pub max_step: i32,
+    pub neighbourhood: Neighbourhood,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            start: 0,
+            end: 9,
+            min_step: 1,
+            max_step: 1,
+            neighbourhood: Neighbourhood::Four,
+        }
+    }
+}
+
+impl Rules {
+    pub fn with_heights(mut self, start: i32, end: i32) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    /// Allows steps that change the height by `min_step..=max_step`, e.g. `-1, 1` or `1, k`.
+    pub fn with_steps(mut self, min_step: i32, max_step: i32) -> Self {
+        assert!(
+            min_step <= max_step,
+            "empty step range {min_step}..={max_step}"
+        );
+        self.min_step = min_step;
+        self.max_step = max_step;
+        self
+    }
+
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    /// Whether every step strictly climbs, or every step strictly descends. Otherwise a trail
+    /// can walk in circles, and the number of trails can grow exponentially with the map.
+    pub fn is_monotonic(&self) -> bool {
+        self.min_step > 0 || self.max_step < 0
+    }
+}
+
+/// A set of summits, one bit per summit.
+#[derive(Debug, Clone)]
+struct Bitset {
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    fn new(len: usize) -> Bitset {
+        Bitset {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    fn union_with(&mut self, other: &Bitset) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+/// For every cell, the summits reachable from it and the number of distinct trails to them.
+///
+/// With monotonic rules it is built in one pass over the cells from the summits down, each cell
+/// combines the results of the cells it can step to. Otherwise the summits are found by searching
+/// backwards from each summit, and trails are not counted.
+#[derive(Debug, Clone)]
+pub struct TrailTable {
+    rules: Rules,
+    width: usize,
+    height: usize,
+    heights: Vec<i32>,
+    summits: Vec<Bitset>,
+    trails: Option<Vec<u64>>,
+}
+
+impl TrailTable {
+    pub fn new(map: &[Vec<i32>], rules: &Rules) -> TrailTable {
+        let height = map.len();
+        let width = map.first().map_or(0, Vec::len);
+        let heights: Vec<i32> = map.iter().flatten().copied().collect();
+
+        let summit_count = heights.iter().filter(|value| **value == rules.end).count();
+        let mut table = TrailTable {
+            rules: rules.clone(),
+            width,
+            height,
+            heights,
+            summits: vec![Bitset::new(summit_count); width * height],
+            trails: None,
+        };
+
+        if rules.is_monotonic() {
+            table.count_trails();
+        } else {
+            table.search_summits();
+        }
+
+        table
+    }
+
+    /// Returns the cells a trail can step to from the cell.
+    fn steps(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = (index % self.width, index / self.width);
+        let value = self.heights[index];
+        self.rules
+            .neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |(dx, dy)| {
+                let nx = x.checked_add_signed(*dx).filter(|nx| *nx < self.width)?;
+                let ny = y.checked_add_signed(*dy).filter(|ny| *ny < self.height)?;
+                Some(ny * self.width + nx)
+            })
+            .filter(move |next| {
+                let step = self.heights[*next] - value;
+                self.rules.min_step <= step && step <= self.rules.max_step
+            })
+    }
+
+    fn count_trails(&mut self) {
+        // Steps climb, or descend, so visit the cells from the other side
+        let mut cells: Vec<usize> = (0..self.heights.len()).collect();
+        if self.rules.min_step > 0 {
+            cells.sort_by_key(|index| std::cmp::Reverse(self.heights[*index]));
+        } else {
+            cells.sort_by_key(|index| self.heights[*index]);
+        }
+
+        let mut trails = vec![0; self.heights.len()];
+        let mut next_summit = 0;
+        for index in cells {
+            if self.heights[index] == self.rules.end {
+                self.summits[index].insert(next_summit);
+                trails[index] = 1;
+                next_summit += 1;
+                continue;
+            }
+
+            let mut summits = self.summits[index].clone();
+            let mut count = 0;
+            for next in self.steps(index) {
+                summits.union_with(&self.summits[next]);
+                count += trails[next];
+            }
+
+            self.summits[index] = summits;
+            trails[index] = count;
+        }
+
+        self.trails = Some(trails);
+    }
+
+    fn search_summits(&mut self) {
+        // For each cell, the cells that can step to it
+        let mut predecessors = vec![Vec::new(); self.heights.len()];
+        for index in 0..self.heights.len() {
+            if self.heights[index] == self.rules.end {
+                continue;
+            }
+            for next in self.steps(index) {
+                predecessors[next].push(index);
+            }
+        }
+
+        let summits: Vec<usize> = (0..self.heights.len())
+            .filter(|index| self.heights[*index] == self.rules.end)
+            .collect();
+        for (summit_index, summit) in summits.into_iter().enumerate() {
+            self.summits[summit].insert(summit_index);
+
+            let mut queue = VecDeque::from([summit]);
+            let mut seen = vec![false; self.heights.len()];
+            seen[summit] = true;
+            while let Some(index) = queue.pop_front() {
+                for previous in &predecessors[index] {
+                    if !seen[*previous] {
+                        seen[*previous] = true;
+                        self.summits[*previous].insert(summit_index);
+                        queue.push_back(*previous);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the cells where trails start.
+    pub fn trailheads(&self) -> Vec<(usize, usize)> {
+        (0..self.heights.len())
+            .filter(|index| self.heights[*index] == self.rules.start)
+            .map(|index| (index % self.width, index / self.width))
+            .collect()
+    }
+
+    /// Returns the number of summits reachable from the cell.
+    pub fn score(&self, (x, y): (usize, usize)) -> usize {
+        self.summits[y * self.width + x].len()
+    }
+
+    /// Returns the number of distinct trails from the cell to a summit, `None` if the rules are
+    /// not monotonic.
+    pub fn rating(&self, (x, y): (usize, usize)) -> Option<u64> {
+        Some(self.trails.as_ref()?[y * self.width + x])
+    }
+
+    /// Returns up to `limit` trails from the cell, each as the cells it passes through. A trail
+    /// never visits a cell twice.
+    pub fn list_trails(&self, (x, y): (usize, usize), limit: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut trails = Vec::new();
+        let mut path = vec![y * self.width + x];
+        let mut on_path = vec![false; self.heights.len()];
+        on_path[path[0]] = true;
+        self.extend_trails(&mut path, &mut on_path, limit, &mut trails);
+
+        trails
+    }
+
+    fn extend_trails(
+        &self,
+        path: &mut Vec<usize>,
+        on_path: &mut [bool],
+        limit: usize,
+        trails: &mut Vec<Vec<(usize, usize)>>,
+    ) {
+        let index = *path.last().unwrap();
+        if trails.len() >= limit || self.summits[index].is_empty() {
+            return;
+        }
+        if self.heights[index] == self.rules.end {
+            let trail = path
+                .iter()
+                .map(|index| (index % self.width, index / self.width))
+                .collect();
+            trails.push(trail);
+            return;
+        }
+
+        let steps: Vec<usize> = self.steps(index).collect();
+        for next in steps {
+            if on_path[next] {
+                continue;
+            }
+
+            on_path[next] = true;
+            path.push(next);
+            self.extend_trails(path, on_path, limit, trails);
+            path.pop();
+            on_path[next] = false;
+        }
+    }
+}